@@ -0,0 +1,145 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// The result of solving one part of a day.
+///
+/// Integers compare by value whichever variant holds them, so `Int(5) == Unsigned(5)`.
+/// Numbers and strings are not ordered against each other, and `Unsolved` (the
+/// template's placeholder) never matches a recorded answer.
+#[derive(Debug, Clone, Default)]
+pub enum Answer {
+    Int(i64),
+    Unsigned(u64),
+    Str(String),
+    #[default]
+    Unsolved,
+}
+
+impl Answer {
+    /// Reads a recorded answer, preferring `Int`, then `Unsigned`, then `Str`.
+    pub fn parse(s: &str) -> Self {
+        if let Ok(n) = s.parse::<i64>() {
+            Answer::Int(n)
+        } else if let Ok(n) = s.parse::<u64>() {
+            Answer::Unsigned(n)
+        } else {
+            Answer::Str(s.to_owned())
+        }
+    }
+
+    pub fn as_i128(&self) -> Option<i128> {
+        match self {
+            Answer::Int(n) => Some(*n as i128),
+            Answer::Unsigned(n) => Some(*n as i128),
+            _ => None,
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        !matches!(self, Answer::Unsolved)
+    }
+
+    /// Whether this is a solved answer equal to the recorded answer `expected`.
+    pub fn matches(&self, expected: &str) -> bool {
+        self.is_solved() && self.to_string() == expected
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::Unsigned(n) => write!(f, "{}", n),
+            Answer::Str(s) => write!(f, "{}", s),
+            Answer::Unsolved => write!(f, "unsolved"),
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Answer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Answer::Str(a), Answer::Str(b)) => a.partial_cmp(b),
+            (Answer::Unsolved, Answer::Unsolved) => Some(Ordering::Equal),
+            _ => self.as_i128()?.partial_cmp(&other.as_i128()?),
+        }
+    }
+}
+
+macro_rules! answer_from {
+    ($variant:ident, $target:ty, $($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::$variant(n as $target)
+                }
+            }
+        )*
+    };
+}
+
+answer_from!(Int, i64, i8, i16, i32, i64, isize);
+answer_from!(Unsigned, u64, u8, u16, u32, u64, usize);
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Str(s.to_owned())
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Str(s)
+    }
+}
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    /// `None` is `Unsolved`.
+    fn from(x: Option<T>) -> Self {
+        x.map(|x| x.into()).unwrap_or(Answer::Unsolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_and_display() {
+        assert_eq!(Answer::from(-3i32).to_string(), "-3");
+        assert_eq!(Answer::from(7usize).to_string(), "7");
+        assert_eq!(Answer::from("abc").to_string(), "abc");
+        assert_eq!(Answer::from(None::<i64>).to_string(), "unsolved");
+        assert_eq!(Answer::from(Some(4u8)), Answer::Unsigned(4));
+        assert!(!Answer::default().is_solved());
+    }
+
+    #[test]
+    fn compare() {
+        assert_eq!(Answer::Int(5), Answer::Unsigned(5));
+        assert!(Answer::Int(-1) < Answer::Unsigned(u64::MAX));
+        assert!(Answer::from("b") > Answer::from("a"));
+        assert_eq!(Answer::from("5").partial_cmp(&Answer::Int(5)), None);
+        assert_ne!(Answer::from("5"), Answer::Int(5));
+        assert_eq!(Answer::Unsolved, Answer::Unsolved);
+        assert_ne!(Answer::Unsolved, Answer::Int(0));
+    }
+
+    #[test]
+    fn parse_and_match() {
+        assert_eq!(Answer::parse("-12"), Answer::Int(-12));
+        assert!(matches!(Answer::parse("18446744073709551615"), Answer::Unsigned(_)));
+        assert!(matches!(Answer::parse("ab,c"), Answer::Str(_)));
+        assert!(Answer::Unsigned(1748).matches("1748"));
+        assert!(Answer::from("ab,c").matches("ab,c"));
+        assert!(Answer::from("5").matches("5"));
+        assert!(!Answer::Unsolved.matches("unsolved"));
+        assert!(!Answer::Int(1).matches("2"));
+    }
+}
@@ -0,0 +1,246 @@
+use crate::answer::Answer;
+use crate::input::InputSource;
+use crate::parse::ParseError;
+use crate::solution::Solution;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+pub const ANSWERS_VAR: &str = "AOC_ANSWERS";
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// Expected answers keyed by day, input name and part, read from a TOML file such as:
+///
+/// ```toml
+/// [day01.input]
+/// part1 = 2176849
+/// part2 = "23384288"
+///
+/// [day08.input]
+/// part1 = 351
+/// part2 = 1259
+/// ```
+///
+/// Only table headers, comments and `part1`/`part2` keys with string or integer values are supported.
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct Answers {
+    answers: BTreeMap<(u32, String, u32), String>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { expected } => write!(f, "FAIL, expected {}", expected),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl Answers {
+    /// Reads `AOC_ANSWERS` if set, otherwise `answers.toml` in the package root
+    /// (when run by cargo) or the current directory. A missing file gives no answers.
+    pub fn load() -> Result<Self, String> {
+        if let Some(path) = std::env::var_os(ANSWERS_VAR) {
+            return Answers::from_file(Path::new(&path));
+        }
+
+        let path = std::env::var_os("CARGO_MANIFEST_DIR")
+            .map(|dir| PathBuf::from(dir).join(ANSWERS_FILE))
+            .filter(|path| path.exists())
+            .unwrap_or_else(|| PathBuf::from(ANSWERS_FILE));
+
+        match path.exists() {
+            true => Answers::from_file(&path),
+            false => Ok(Answers::default()),
+        }
+    }
+
+    /// As `load`, but reports a broken answers file on stderr and carries on without it.
+    pub fn load_or_default() -> Self {
+        Answers::load().unwrap_or_else(|e| {
+            eprintln!("Ignoring answers file: {}", e);
+            Answers::default()
+        })
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let s = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Answers::try_from(s.as_str()).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn get(&self, day: u32, input: &str, part: u32) -> Option<&str> {
+        self.answers.get(&(day, input.to_owned(), part)).map(|x| x.as_str())
+    }
+
+    pub fn insert(&mut self, day: u32, input: &str, part: u32, answer: &str) {
+        self.answers.insert((day, input.to_owned(), part), answer.to_owned());
+    }
+
+    /// Names of every input with a recorded answer for `day`.
+    pub fn inputs(&self, day: u32) -> Vec<&str> {
+        let mut inputs = self.answers.keys()
+            .filter(|(d, _, _)| *d == day)
+            .map(|(_, input, _)| input.as_str())
+            .collect::<Vec<_>>();
+        inputs.dedup();
+        inputs
+    }
+
+    pub fn check(&self, day: u32, input: &str, part: u32, actual: &Answer) -> Verdict {
+        match self.get(day, input, part) {
+            Some(expected) if actual.matches(expected) => Verdict::Pass,
+            Some(expected) => Verdict::Fail { expected: expected.to_owned() },
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+impl TryFrom<&str> for Answers {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let mut answers = Answers::default();
+        let mut table: Option<(u32, String)> = None;
+
+        for (i, line) in crate::input_as_lines(s).iter().enumerate() {
+            let at = |e: ParseError| e.with_line(i + 1);
+            let text = strip_comment(line).trim();
+            if text.is_empty() {
+                continue;
+            }
+
+            if let Some(header) = text.strip_prefix('[').and_then(|x| x.strip_suffix(']')) {
+                table = Some(parse_header(line, header).map_err(at)?);
+                continue;
+            }
+
+            let (key, value) = text.split_once('=')
+                .ok_or_else(|| at(ParseError::at_token("Expected 'key = value'", line, text)))?;
+            let (key, value) = (key.trim(), value.trim());
+            let part = match key {
+                "part1" => 1,
+                "part2" => 2,
+                _ => return Err(at(ParseError::at_token("Expected part1 or part2", line, key))),
+            };
+            let (day, input) = table.as_ref()
+                .ok_or_else(|| at(ParseError::at_token("Answer outside a [dayNN.input] table", line, key)))?;
+
+            answers.insert(*day, input, part, &parse_value(line, value).map_err(at)?);
+        }
+
+        Ok(answers)
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => (),
+        }
+    }
+    line
+}
+
+fn parse_header(line: &str, header: &str) -> Result<(u32, String), ParseError> {
+    let err = || ParseError::at_token("Expected a [dayNN.input] table", line, header);
+    let (day, input) = header.trim().split_once('.').ok_or_else(err)?;
+    let day = day.trim().strip_prefix("day").and_then(|x| x.parse::<u32>().ok()).ok_or_else(err)?;
+    let input = input.trim().trim_matches('"');
+    if input.is_empty() {
+        return Err(err());
+    }
+    Ok((day, input.to_owned()))
+}
+
+fn parse_value(line: &str, value: &str) -> Result<String, ParseError> {
+    if let Some(s) = value.strip_prefix('"').and_then(|x| x.strip_suffix('"')) {
+        return Ok(s.to_owned());
+    }
+    let digits = value.strip_prefix('-').unwrap_or(value).replace('_', "");
+    if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(value.replace('_', ""));
+    }
+    Err(ParseError::at_token("Expected a string or integer", line, value))
+}
+
+/// Test helper behind `answer_tests!`: solves one part against the current input
+/// (see `InputSource::from_env`) and fails only when a recorded answer disagrees.
+pub fn assert_answer<S: Solution>(part: u32) {
+    let answers = Answers::load().unwrap_or_else(|e| panic!("{}", e));
+    let source = InputSource::from_env();
+    let input = source.load(S::DAY, S::default_input).unwrap_or_else(|e| panic!("{}", e));
+    let model = S::parse(&input).unwrap_or_else(|e| panic!("{}", e.diagnostic(&input)));
+
+    let actual: Answer = match part {
+        1 => S::part1(&model).into(),
+        _ => S::part2(&model).into(),
+    };
+
+    match answers.check(S::DAY, &source.name(), part, &actual) {
+        Verdict::Pass => (),
+        Verdict::Unknown => eprintln!("Day {:02} part {} ({}): no recorded answer for {}", S::DAY, part, source.name(), actual),
+        verdict => panic!("Day {:02} part {} ({}): got {}, {}", S::DAY, part, source.name(), actual, verdict),
+    }
+}
+
+/// Generates `solve_part1` and `solve_part2` tests checking a day against the answers file,
+/// and `solve_examples` checking it against every registered `Example`.
+#[macro_export]
+macro_rules! answer_tests {
+    ($day:ty) => {
+        #[test]
+        fn solve_part1() {
+            $crate::assert_answer::<$day>(1);
+        }
+
+        #[test]
+        fn solve_part2() {
+            $crate::assert_answer::<$day>(2);
+        }
+
+        #[test]
+        fn solve_examples() {
+            $crate::assert_examples::<$day>();
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_answers() {
+        let answers = Answers::try_from("# comment\n[day01.input]\npart1 = 2176849 # trailing\npart2 = \"a#b\"\n\n[day08.\"08test1\"]\npart1 = -1_000\n").unwrap();
+        assert_eq!(answers.get(1, "input", 1), Some("2176849"));
+        assert_eq!(answers.get(1, "input", 2), Some("a#b"));
+        assert_eq!(answers.get(8, "08test1", 1), Some("-1000"));
+        assert_eq!(answers.get(8, "08test1", 2), None);
+        assert_eq!(answers.inputs(8), vec!["08test1"]);
+
+        assert_eq!(answers.check(1, "input", 1, &Answer::Unsigned(2176849)), Verdict::Pass);
+        assert_eq!(answers.check(1, "input", 1, &Answer::Int(1)), Verdict::Fail { expected: "2176849".to_owned() });
+        assert_eq!(answers.check(1, "input", 1, &Answer::Unsolved), Verdict::Fail { expected: "2176849".to_owned() });
+        assert_eq!(answers.check(1, "input", 2, &Answer::from("a#b")), Verdict::Pass);
+        assert_eq!(answers.check(2, "input", 1, &Answer::Int(1)), Verdict::Unknown);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(Answers::try_from("part1 = 1").unwrap_err().line, 1);
+        assert_eq!(Answers::try_from("[day01.input]\npart3 = 1").unwrap_err().column, 1);
+        assert_eq!(Answers::try_from("[day01.input]\npart1 = abc").unwrap_err().column, 9);
+        assert_eq!(Answers::try_from("[dayx.input]").unwrap_err().line, 1);
+    }
+}
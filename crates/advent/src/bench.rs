@@ -0,0 +1,229 @@
+use crate::parse::ParseError;
+use crate::solution::Solution;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+pub enum BenchFormat {
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+impl TryFrom<&str> for BenchFormat {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "table" => Ok(BenchFormat::Table),
+            "json" => Ok(BenchFormat::Json),
+            "csv" => Ok(BenchFormat::Csv),
+            _ => Err(format!("Unknown bench format: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct BenchOptions {
+    pub runs: usize,
+    pub format: BenchFormat,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        BenchOptions { runs: 10, format: BenchFormat::Table }
+    }
+}
+
+impl BenchOptions {
+    /// Parses `--bench`, `--runs <n>` and `--format <table|json|csv>` out of `args`.
+    /// Returns `None` unless `--bench` was given, along with the remaining arguments.
+    pub fn from_args<I>(args: I) -> Result<(Option<Self>, Vec<String>), String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut bench = false;
+        let mut options = BenchOptions::default();
+        let mut rest = vec![];
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--bench" => bench = true,
+                "--runs" | "--format" => {
+                    let value = args.next().ok_or_else(|| format!("Missing value for {}", arg))?;
+                    if arg == "--runs" {
+                        options.runs = value.parse::<usize>().ok().filter(|x| *x > 0)
+                            .ok_or_else(|| format!("Invalid run count: {}", value))?;
+                    } else {
+                        options.format = BenchFormat::try_from(value.as_str())?;
+                    }
+                },
+                _ => rest.push(arg),
+            }
+        }
+
+        Ok((bench.then_some(options), rest))
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct BenchStats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+impl BenchStats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty());
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let n = sorted.len();
+        let median = match n % 2 {
+            0 => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+            _ => sorted[n / 2],
+        };
+        let mean = sorted.iter().sum::<Duration>() / n as u32;
+
+        BenchStats { min: sorted[0], median, mean }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct BenchResult {
+    pub day: u32,
+    pub runs: usize,
+    pub parse: BenchStats,
+    pub part1: BenchStats,
+    pub part2: BenchStats,
+}
+
+impl BenchResult {
+    fn steps(&self) -> [(&'static str, &BenchStats); 3] {
+        [("parse", &self.parse), ("part1", &self.part1), ("part2", &self.part2)]
+    }
+}
+
+/// Times parsing and each part separately over `runs` runs.
+pub fn bench<S: Solution>(s: &str, runs: usize) -> Result<BenchResult, ParseError> {
+    let mut parse = vec![];
+    let mut part1 = vec![];
+    let mut part2 = vec![];
+
+    for _ in 0..runs {
+        let t = Instant::now();
+        let model = black_box(S::parse(black_box(s))?);
+        parse.push(t.elapsed());
+
+        let t = Instant::now();
+        black_box(S::part1(&model));
+        part1.push(t.elapsed());
+
+        let t = Instant::now();
+        black_box(S::part2(&model));
+        part2.push(t.elapsed());
+    }
+
+    Ok(BenchResult {
+        day: S::DAY,
+        runs,
+        parse: BenchStats::from_samples(&parse),
+        part1: BenchStats::from_samples(&part1),
+        part2: BenchStats::from_samples(&part2),
+    })
+}
+
+pub fn format_bench(results: &[BenchResult], format: BenchFormat) -> String {
+    match format {
+        BenchFormat::Table => format_table(results),
+        BenchFormat::Json => format_json(results),
+        BenchFormat::Csv => format_csv(results),
+    }
+}
+
+fn format_table(results: &[BenchResult]) -> String {
+    let mut s = format!("{:<4} {:<6} {:>5} {:>12} {:>12} {:>12}\n", "day", "step", "runs", "min", "median", "mean");
+    for r in results {
+        for (step, stats) in r.steps() {
+            s += &format!("{:<4} {:<6} {:>5} {:>12} {:>12} {:>12}\n",
+                format!("{:02}", r.day), step, r.runs,
+                format!("{:.3?}", stats.min), format!("{:.3?}", stats.median), format!("{:.3?}", stats.mean));
+        }
+    }
+    s
+}
+
+fn format_csv(results: &[BenchResult]) -> String {
+    let mut s = "day,step,runs,min_ns,median_ns,mean_ns\n".to_string();
+    for r in results {
+        for (step, stats) in r.steps() {
+            s += &format!("{},{},{},{},{},{}\n",
+                r.day, step, r.runs, stats.min.as_nanos(), stats.median.as_nanos(), stats.mean.as_nanos());
+        }
+    }
+    s
+}
+
+fn format_json(results: &[BenchResult]) -> String {
+    let stats = |x: &BenchStats| format!("{{\"min_ns\":{},\"median_ns\":{},\"mean_ns\":{}}}",
+        x.min.as_nanos(), x.median.as_nanos(), x.mean.as_nanos());
+
+    let days = results.iter()
+        .map(|r| format!("{{\"day\":{},\"runs\":{},\"parse\":{},\"part1\":{},\"part2\":{}}}",
+            r.day, r.runs, stats(&r.parse), stats(&r.part1), stats(&r.part2)))
+        .collect::<Vec<_>>();
+
+    format!("[{}]\n", days.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(x: u64) -> Duration {
+        Duration::from_millis(x)
+    }
+
+    #[test]
+    fn stats() {
+        let s = BenchStats::from_samples(&[ms(5), ms(1), ms(3)]);
+        assert_eq!(s, BenchStats { min: ms(1), median: ms(3), mean: ms(3) });
+
+        let s = BenchStats::from_samples(&[ms(4), ms(1), ms(2), ms(9)]);
+        assert_eq!(s, BenchStats { min: ms(1), median: ms(3), mean: ms(4) });
+    }
+
+    #[test]
+    fn options_from_args() {
+        let args = |s: &str| s.split_whitespace().map(|x| x.to_owned()).collect::<Vec<_>>();
+
+        let (options, rest) = BenchOptions::from_args(args("6 --bench --runs 3 --format csv")).unwrap();
+        assert_eq!(options, Some(BenchOptions { runs: 3, format: BenchFormat::Csv }));
+        assert_eq!(rest, vec!["6"]);
+
+        let (options, _) = BenchOptions::from_args(args("6")).unwrap();
+        assert_eq!(options, None);
+
+        assert!(BenchOptions::from_args(args("--bench --runs 0")).is_err());
+        assert!(BenchOptions::from_args(args("--bench --format xml")).is_err());
+    }
+
+    #[test]
+    fn formats() {
+        let stats = BenchStats { min: ms(1), median: ms(2), mean: ms(3) };
+        let results = [BenchResult { day: 6, runs: 2, parse: stats, part1: stats, part2: stats }];
+
+        let csv = format_bench(&results, BenchFormat::Csv);
+        assert_eq!(csv.lines().nth(1), Some("6,parse,2,1000000,2000000,3000000"));
+        assert_eq!(csv.lines().count(), 4);
+
+        let json = format_bench(&results, BenchFormat::Json);
+        assert!(json.starts_with("[{\"day\":6,\"runs\":2,\"parse\":{\"min_ns\":1000000,"));
+
+        let table = format_bench(&results, BenchFormat::Table);
+        assert!(table.lines().nth(3).unwrap().starts_with("06   part2      2"));
+    }
+}
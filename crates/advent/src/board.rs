@@ -0,0 +1,264 @@
+use crate::{input_as_lines, Compass, ParseError, Point32};
+use std::ops::{Index, IndexMut};
+
+/// A dense, row-major grid addressed by `Point32` with `(0, 0)` at the top left.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Board2D<T> {
+    vec: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Board2D<T> {
+    pub fn new(vec: Vec<T>, height: usize, width: usize) -> Self {
+        assert!(vec.len() == width * height);
+        Self { vec, width, height }
+    }
+
+    pub fn filled(value: T, height: usize, width: usize) -> Self
+    where
+        T: Clone,
+    {
+        Self::new(vec![value; width * height], height, width)
+    }
+
+    /// Builds a board from a rectangular character grid, mapping each cell with `f`.
+    /// `f` may capture state, e.g. to record where a marker character was found.
+    pub fn parse<F>(s: &str, mut f: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(char, Point32) -> T,
+    {
+        Self::try_parse(s, |c, p| Ok(f(c, p)))
+    }
+
+    /// As `parse`, but the cell mapping may fail. Errors without a position
+    /// are reported at the offending cell.
+    pub fn try_parse<F>(s: &str, mut f: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(char, Point32) -> Result<T, ParseError>,
+    {
+        let lines = input_as_lines(s);
+        let height = lines.len();
+        let width = lines.first().map(|x| x.chars().count()).unwrap_or(0);
+        let mut vec = Vec::with_capacity(width * height);
+
+        for (ey, line) in lines.iter().enumerate() {
+            let len = line.chars().count();
+            if len != width {
+                let msg = format!("Expected {} columns, found {}", width, len);
+                return Err(ParseError::new(msg, line).with_line(ey + 1));
+            }
+
+            for (ex, c) in line.chars().enumerate() {
+                let p = Point32 { x: ex as i32, y: ey as i32 };
+                let cell = f(c, p).map_err(|e| match e.line {
+                    0 => e.with_line(ey + 1).with_column(ex + 1),
+                    _ => e,
+                })?;
+                vec.push(cell);
+            }
+        }
+
+        Ok(Self::new(vec, height, width))
+    }
+
+    pub fn width(&self) -> usize { self.width }
+    pub fn height(&self) -> usize { self.height }
+
+    pub fn index(&self, x: i64, y: i64) -> Option<&T> {
+        if y >= 0 && x >= 0 && (y as usize) < self.height && (x as usize) < self.width  {
+            Some(&self.vec[((y as usize) * self.width) + (x as usize)])
+        } else {
+            None
+        }
+    }
+
+    pub fn in_bounds(&self, p: Point32) -> bool {
+        p.x >= 0 && p.y >= 0 && (p.x as usize) < self.width && (p.y as usize) < self.height
+    }
+
+    fn offset_of(&self, p: Point32) -> Option<usize> {
+        self.in_bounds(p).then(|| (p.y as usize) * self.width + (p.x as usize))
+    }
+
+    fn point_of(&self, i: usize) -> Point32 {
+        Point32 { x: (i % self.width) as i32, y: (i / self.width) as i32 }
+    }
+
+    pub fn get(&self, p: Point32) -> Option<&T> {
+        self.offset_of(p).map(|i| &self.vec[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point32) -> Option<&mut T> {
+        self.offset_of(p).map(|i| &mut self.vec[i])
+    }
+
+    /// Replaces the value at `p`, returning the old value, or `None` when out of bounds.
+    pub fn set(&mut self, p: Point32, value: T) -> Option<T> {
+        self.get_mut(p).map(|x| std::mem::replace(x, value))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.vec[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> + '_ {
+        assert!(x < self.width);
+        self.vec.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.vec.iter()
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point32> + '_ {
+        (0..self.vec.len()).map(|i| self.point_of(i))
+    }
+
+    pub fn iter_with_points(&self) -> impl Iterator<Item = (Point32, &T)> + '_ {
+        self.vec.iter().enumerate().map(|(i, v)| (self.point_of(i), v))
+    }
+
+    /// The in-bounds neighbours of `p` to the north, east, south and west.
+    pub fn neighbours4(&self, p: Point32) -> impl Iterator<Item = (Compass, Point32, &T)> + '_ {
+        self.neighbours_impl(p, Compass::cardinals())
+    }
+
+    /// The in-bounds neighbours of `p` in all eight directions.
+    pub fn neighbours8(&self, p: Point32) -> impl Iterator<Item = (Compass, Point32, &T)> + '_ {
+        self.neighbours_impl(p, Compass::all())
+    }
+
+    fn neighbours_impl<const N: usize>(&self, p: Point32, dirs: [Compass; N]) -> impl Iterator<Item = (Compass, Point32, &T)> + '_ {
+        dirs.into_iter().filter_map(move |dir| {
+            let n = p + dir.delta();
+            self.get(n).map(|v| (dir, n, v))
+        })
+    }
+
+    pub fn find(&self, value: &T) -> Option<Point32>
+    where
+        T: PartialEq,
+    {
+        self.vec.iter().position(|x| x == value).map(|i| self.point_of(i))
+    }
+
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Point32> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter_with_points().filter(move |(_, x)| *x == value).map(|(p, _)| p)
+    }
+}
+
+impl TryFrom<&str> for Board2D<char> {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Board2D::parse(s, |c, _| c)
+    }
+}
+
+impl<T> Index<Point32> for Board2D<T> {
+    type Output = T;
+
+    fn index(&self, p: Point32) -> &T {
+        self.get(p).unwrap_or_else(|| panic!("{:?} is outside the board", p))
+    }
+}
+
+impl<T> IndexMut<Point32> for Board2D<T> {
+    fn index_mut(&mut self, p: Point32) -> &mut T {
+        self.get_mut(p).unwrap_or_else(|| panic!("{:?} is outside the board", p))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pt(x: i32, y: i32) -> Point32 {
+        Point32 { x, y }
+    }
+
+    fn example() -> Board2D<char> {
+        Board2D::new("abcdef".chars().collect(), 2, 3)
+    }
+
+    #[test]
+    fn parse() {
+        let b = Board2D::try_from("abc\r\ndef\r\n").unwrap();
+        assert_eq!(b, example());
+
+        let mut marker = None;
+        let b = Board2D::parse("..\n.^", |c, p| {
+            if c == '^' { marker = Some(p); }
+            c == '.'
+        }).unwrap();
+        assert_eq!(marker, Some(pt(1, 1)));
+        assert_eq!(b.iter().filter(|x| **x).count(), 3);
+
+        let err = Board2D::try_from("abc\nde\nfgh").unwrap_err();
+        assert_eq!(err.line, 2);
+
+        let err = Board2D::try_parse("0123\n45x7", |c, _| {
+            c.to_digit(10).ok_or_else(|| ParseError::new("Expected a digit", &c.to_string()))
+        }).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+    }
+
+    #[test]
+    fn get_and_set() {
+        let mut b = example();
+        assert!(b.in_bounds(pt(2, 1)));
+        assert!(!b.in_bounds(pt(3, 0)));
+        assert!(!b.in_bounds(pt(0, -1)));
+        assert_eq!(b.get(pt(1, 1)), Some(&'e'));
+        assert_eq!(b[pt(2, 0)], 'c');
+        assert_eq!(b.get(pt(0, 2)), None);
+
+        assert_eq!(b.set(pt(1, 1), 'x'), Some('e'));
+        assert_eq!(b.set(pt(5, 5), 'x'), None);
+        *b.get_mut(pt(0, 0)).unwrap() = 'y';
+        b[pt(2, 1)] = 'z';
+        assert_eq!(b.iter().collect::<String>(), "ybcdxz");
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let b = example();
+        assert_eq!(b.row(1), &['d', 'e', 'f']);
+        assert_eq!(b.rows().count(), 2);
+        assert_eq!(b.column(1).collect::<String>(), "be");
+        assert_eq!(b.columns().map(|c| c.collect::<String>()).collect::<Vec<_>>(), vec!["ad", "be", "cf"]);
+        assert_eq!(b.points().last(), Some(pt(2, 1)));
+        assert_eq!(b.iter_with_points().nth(4), Some((pt(1, 1), &'e')));
+    }
+
+    #[test]
+    fn neighbours() {
+        let b = example();
+        let n4 = b.neighbours4(pt(0, 0)).collect::<Vec<_>>();
+        assert_eq!(n4, vec![(Compass::East, pt(1, 0), &'b'), (Compass::South, pt(0, 1), &'d')]);
+
+        let n8 = b.neighbours8(pt(1, 0)).map(|(_, _, c)| *c).collect::<String>();
+        assert_eq!(n8, "cfeda");
+    }
+
+    #[test]
+    fn find() {
+        let b = Board2D::new("a.a.".chars().collect(), 2, 2);
+        assert_eq!(b.find(&'.'), Some(pt(1, 0)));
+        assert_eq!(b.find(&'x'), None);
+        assert_eq!(b.find_all(&'a').collect::<Vec<_>>(), vec![pt(0, 0), pt(0, 1)]);
+        assert_eq!(Board2D::filled(0u8, 2, 3).iter().count(), 6);
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A cycle in the states `x0, step(x0), step(step(x0)), ...`: after `prefix` steps
+/// the sequence reaches `start`, and returns to it every `period` steps.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Cycle<S> {
+    pub start: S,
+    pub prefix: usize,
+    pub period: usize,
+}
+
+impl<S> Cycle<S> {
+    /// The earliest step index holding the same state as step `n`.
+    pub fn position(&self, n: usize) -> usize {
+        match n < self.prefix {
+            true => n,
+            false => self.prefix + (n - self.prefix) % self.period,
+        }
+    }
+}
+
+// Each detector takes a `step` returning `None` when the sequence ends, e.g. when a
+// walker leaves its map, and then returns `None` as there is no cycle.
+
+/// Floyd's tortoise and hare, which keeps only two states.
+pub fn floyd<S, F>(x0: S, mut step: F) -> Option<Cycle<S>>
+where
+    S: Eq + Clone,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut tortoise = step(&x0)?;
+    let mut hare = step(&tortoise)?;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        hare = step(&hare)?;
+    }
+
+    let mut prefix = 0;
+    tortoise = x0;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        prefix += 1;
+    }
+
+    let mut period = 1;
+    hare = step(&tortoise)?;
+    while tortoise != hare {
+        hare = step(&hare)?;
+        period += 1;
+    }
+
+    Some(Cycle { start: tortoise, prefix, period })
+}
+
+/// Brent's algorithm, which keeps two states and usually steps less than `floyd`.
+pub fn brent<S, F>(x0: S, mut step: F) -> Option<Cycle<S>>
+where
+    S: Eq + Clone,
+    F: FnMut(&S) -> Option<S>,
+{
+    let (mut power, mut period) = (1, 1);
+    let mut tortoise = x0.clone();
+    let mut hare = step(&x0)?;
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare)?;
+        period += 1;
+    }
+
+    let mut prefix = 0;
+    tortoise = x0.clone();
+    hare = x0;
+    for _ in 0..period {
+        hare = step(&hare)?;
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        prefix += 1;
+    }
+
+    Some(Cycle { start: tortoise, prefix, period })
+}
+
+/// Remembers every state, so steps each state once at the cost of a map entry per step.
+pub fn find_cycle<S, F>(x0: S, mut step: F) -> Option<Cycle<S>>
+where
+    S: Eq + Hash + Clone,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut seen = HashMap::new();
+    let mut x = x0;
+    let mut i = 0;
+    loop {
+        if let Some(first) = seen.get(&x) {
+            return Some(Cycle { prefix: *first, period: i - first, start: x });
+        }
+        seen.insert(x.clone(), i);
+        x = step(&x)?;
+        i += 1;
+    }
+}
+
+/// The state after `n` steps, skipping whole cycles once one is found, or `None`
+/// when the sequence ends first.
+pub fn fast_forward<S, F>(x0: S, mut step: F, n: usize) -> Option<S>
+where
+    S: Eq + Hash + Clone,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut seen = HashMap::new();
+    let mut states = vec![];
+    let mut x = x0;
+    for i in 0..n {
+        if let Some(first) = seen.get(&x) {
+            return Some(states.swap_remove(first + (n - first) % (i - first)));
+        }
+        seen.insert(x.clone(), i);
+        states.push(x.clone());
+        x = step(&x)?;
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0, 1, ..., 9, then back to 3.
+    fn rho(x: &usize) -> Option<usize> {
+        Some(if *x == 9 { 3 } else { x + 1 })
+    }
+
+    #[test]
+    fn detectors_agree() {
+        let expected = Some(Cycle { start: 3, prefix: 3, period: 7 });
+        assert_eq!(floyd(0, rho), expected);
+        assert_eq!(brent(0, rho), expected);
+        assert_eq!(find_cycle(0, rho), expected);
+
+        let lcg = |x: &u64| Some((x * 1103515245 + 12345) % 1000);
+        let cycle = find_cycle(7, lcg).unwrap();
+        assert_eq!(floyd(7, lcg), Some(cycle.clone()));
+        assert_eq!(brent(7, lcg), Some(cycle));
+
+        let fixed = Some(Cycle { start: 5, prefix: 0, period: 1 });
+        assert_eq!(brent(5, |x| Some(*x)), fixed);
+        assert_eq!(floyd(5, |x| Some(*x)), fixed);
+    }
+
+    #[test]
+    fn ending_sequence() {
+        let ends = |x: &u32| (*x < 100).then_some(x + 1);
+        assert_eq!(floyd(0, ends), None);
+        assert_eq!(brent(0, ends), None);
+        assert_eq!(find_cycle(0, ends), None);
+        assert_eq!(fast_forward(0, ends, 50), Some(50));
+        assert_eq!(fast_forward(0, ends, 101), None);
+    }
+
+    #[test]
+    fn skip_ahead() {
+        let cycle = find_cycle(0, rho).unwrap();
+        assert_eq!(cycle.position(2), 2);
+        assert_eq!(cycle.position(10), 3);
+        assert_eq!(fast_forward(0, rho, 0), Some(0));
+        assert_eq!(fast_forward(0, rho, 10), Some(3));
+        assert_eq!(fast_forward(0, rho, 1_000_000_000), Some(6));
+        assert!((0..40).all(|n| fast_forward(0, rho, n) == Some(n).map(|n| cycle.position(n))));
+    }
+}
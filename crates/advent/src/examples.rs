@@ -0,0 +1,218 @@
+use crate::answer::Answer;
+use crate::answers::Answers;
+use crate::parse::ParseError;
+use crate::solution::Solution;
+use std::path::{Path, PathBuf};
+
+pub const PROBLEM_DIR: &str = "src/problem";
+pub const EXAMPLE_DIR: &str = "src/input";
+
+/// A worked example with the answers it is expected to produce.
+///
+/// Examples come from two places:
+///
+/// * blocks in `src/problem/NN.txt` wrapped in `[example ...]` and `[/example]` lines,
+///   e.g. `[example part1=11 part2=31]` or `[example name=small part2=48]`;
+/// * sidecar files `src/input/<name>.txt` for any input other than `input` that has
+///   a recorded answer for the day in the answers file, e.g. `[day08.08test3]`.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Example {
+    pub name: String,
+    /// Where the example was read from, for reporting failures.
+    pub origin: String,
+    pub input: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Example {
+    pub fn expected(&self, part: u32) -> Option<&str> {
+        match part {
+            1 => self.part1.as_deref(),
+            _ => self.part2.as_deref(),
+        }
+    }
+
+    /// Extracts the annotated examples from a problem description.
+    /// Unnamed examples are called `example1`, `example2`, ... in order.
+    pub fn from_problem(text: &str) -> Result<Vec<Example>, ParseError> {
+        let mut examples = vec![];
+        let mut open: Option<(usize, Example)> = None;
+
+        for (i, line) in crate::input_as_lines(text).into_iter().enumerate() {
+            let at = |e: ParseError| e.with_line(i + 1);
+            let marker = line.trim();
+
+            if marker == "[/example]" {
+                let (start, mut example) = open.take()
+                    .ok_or_else(|| at(ParseError::at_token("Closing an example that was never opened", &line, marker)))?;
+                if example.input.is_empty() {
+                    return Err(ParseError::new("Empty example", "").with_line(start));
+                }
+                example.input.pop();
+                examples.push(example);
+            } else if let Some(attrs) = marker.strip_prefix("[example")
+                .and_then(|x| x.strip_suffix(']'))
+                .filter(|x| x.is_empty() || x.starts_with(' ')) {
+                if open.is_some() {
+                    return Err(at(ParseError::at_token("Examples cannot be nested", &line, marker)));
+                }
+                let mut example = parse_attrs(&line, attrs).map_err(at)?;
+                if example.name.is_empty() {
+                    example.name = format!("example{}", examples.len() + 1);
+                }
+                example.origin = format!("line {}", i + 1);
+                open = Some((i + 1, example));
+            } else if let Some((_, example)) = open.as_mut() {
+                example.input += &line;
+                example.input.push('\n');
+            }
+        }
+
+        match open {
+            Some((start, _)) => Err(ParseError::new("Example is missing [/example]", "").with_line(start)),
+            None => Ok(examples),
+        }
+    }
+
+    /// Loads every example registered for `day`: annotated blocks from the problem
+    /// text followed by sidecar inputs with recorded answers. Missing files give none.
+    pub fn load(day: u32, answers: &Answers) -> Result<Vec<Example>, String> {
+        let path = package_path(PROBLEM_DIR).join(crate::input_file_name(day));
+        let mut examples = match path.exists() {
+            true => {
+                let text = read_file(&path)?;
+                let mut examples = Example::from_problem(&text)
+                    .map_err(|e| format!("{}: {}", path.display(), e))?;
+                for example in examples.iter_mut() {
+                    example.origin = format!("{} {}", path.display(), example.origin);
+                }
+                examples
+            },
+            false => vec![],
+        };
+
+        for name in answers.inputs(day).into_iter().filter(|x| *x != "input") {
+            let path = package_path(EXAMPLE_DIR).join(format!("{}.txt", name));
+            if !path.exists() {
+                continue;
+            }
+            examples.push(Example {
+                name: name.to_owned(),
+                origin: path.display().to_string(),
+                input: read_file(&path)?,
+                part1: answers.get(day, name, 1).map(|x| x.to_owned()),
+                part2: answers.get(day, name, 2).map(|x| x.to_owned()),
+            });
+        }
+
+        Ok(examples)
+    }
+
+    /// The input of the example called `name` for `day`, for use in unit tests.
+    pub fn input_of(day: u32, name: &str) -> String {
+        let answers = Answers::load().unwrap_or_else(|e| panic!("{}", e));
+        Example::load(day, &answers)
+            .unwrap_or_else(|e| panic!("{}", e))
+            .into_iter()
+            .find(|x| x.name == name)
+            .unwrap_or_else(|| panic!("Day {:02} has no example called {}", day, name))
+            .input
+    }
+}
+
+fn parse_attrs(line: &str, attrs: &str) -> Result<Example, ParseError> {
+    let mut example = Example { name: String::new(), origin: String::new(), input: String::new(), part1: None, part2: None };
+
+    for attr in attrs.split_whitespace() {
+        let (key, value) = attr.split_once('=')
+            .ok_or_else(|| ParseError::at_token("Expected 'key=value'", line, attr))?;
+        let value = value.trim_matches('"').to_owned();
+        match key {
+            "name" => example.name = value,
+            "part1" => example.part1 = Some(value),
+            "part2" => example.part2 = Some(value),
+            _ => return Err(ParseError::at_token("Expected name, part1 or part2", line, key)),
+        }
+    }
+
+    Ok(example)
+}
+
+/// The path of `rel` within the package being built or tested, falling back to the current directory.
+fn package_path(rel: &str) -> PathBuf {
+    std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(|dir| PathBuf::from(dir).join(rel))
+        .unwrap_or_else(|| PathBuf::from(rel))
+}
+
+fn read_file(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Test helper behind `answer_tests!`: runs both parts against every registered
+/// example for the day and fails listing each wrong answer.
+pub fn assert_examples<S: Solution>() {
+    let answers = Answers::load().unwrap_or_else(|e| panic!("{}", e));
+    let examples = Example::load(S::DAY, &answers).unwrap_or_else(|e| panic!("{}", e));
+    let mut failures = vec![];
+
+    for example in &examples {
+        let model = match S::parse(&example.input) {
+            Ok(model) => model,
+            Err(e) => {
+                failures.push(format!("{} ({}): {}", example.name, example.origin, e.diagnostic(&example.input)));
+                continue;
+            },
+        };
+
+        for part in 1..=2 {
+            let Some(expected) = example.expected(part) else {
+                continue;
+            };
+            let actual: Answer = match part {
+                1 => S::part1(&model).into(),
+                _ => S::part2(&model).into(),
+            };
+            if !actual.matches(expected) {
+                failures.push(format!("{} ({}) part {}: got {}, expected {}", example.name, example.origin, part, actual, expected));
+            }
+        }
+    }
+
+    if examples.is_empty() {
+        eprintln!("Day {:02}: no examples registered", S::DAY);
+    }
+    assert!(failures.is_empty(), "Day {:02} examples failed:\n{}", S::DAY, failures.join("\n"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_problem() {
+        let text = "For example:\n\n[example part1=3 part2=\"x\"]\n1\n2\n[/example]\n\nAgain:\r\n[example name=small part2=1]\r\n1\r\n[/example]\r\n";
+        let examples = Example::from_problem(text).unwrap();
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].name, "example1");
+        assert_eq!(examples[0].input, "1\n2");
+        assert_eq!(examples[0].expected(1), Some("3"));
+        assert_eq!(examples[0].expected(2), Some("x"));
+        assert_eq!(examples[0].origin, "line 3");
+        assert_eq!(examples[1].name, "small");
+        assert_eq!(examples[1].input, "1");
+        assert_eq!(examples[1].expected(1), None);
+    }
+
+    #[test]
+    fn from_problem_errors() {
+        assert_eq!(Example::from_problem("a\n[example part1=1]\n1").unwrap_err().line, 2);
+        assert_eq!(Example::from_problem("[/example]").unwrap_err().line, 1);
+        assert_eq!(Example::from_problem("[example]\n[example]").unwrap_err().line, 2);
+        assert_eq!(Example::from_problem("[example]\n[/example]").unwrap_err().line, 1);
+
+        let err = Example::from_problem("[example part3=1]").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 10));
+    }
+}
@@ -0,0 +1,212 @@
+use std::fmt;
+use std::io::{ErrorKind, Write};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+pub const AOC_URL: &str = "https://adventofcode.com";
+pub const CONTACT_VAR: &str = "AOC_CONTACT";
+
+/// The contact from `AOC_CONTACT` that requests are identified by, as adventofcode.com
+/// asks automated tools to say who is running them.
+pub fn contact_from_env() -> Option<String> {
+    std::env::var(CONTACT_VAR).ok().filter(|x| !x.trim().is_empty())
+}
+
+pub fn user_agent(contact: &str) -> String {
+    format!("advent2024 input cache and submit client ({})", contact.trim())
+}
+
+/// Why a request got no response.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum HttpError {
+    /// `curl`, which sends every request, is not installed or not on the `PATH`.
+    CurlMissing,
+    /// There is no contact to identify the request by.
+    NoContact,
+    /// curl ran but the request failed, e.g. the host could not be reached.
+    Failed(String),
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpError::CurlMissing => write!(f, "curl is needed to reach adventofcode.com but is not on the PATH"),
+            HttpError::NoContact => write!(f, "Set {} to an email or URL adventofcode.com can reach you at to send requests", CONTACT_VAR),
+            HttpError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+impl From<HttpError> for String {
+    fn from(e: HttpError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Whether `curl` can be run, checked once per process.
+pub fn curl_available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| Command::new("curl")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|x| x.success()))
+}
+
+/// A small HTTP client for adventofcode.com that shells out to `curl`, which
+/// gives us HTTPS without any crate dependencies. Works against any base URL,
+/// e.g. a local server in tests. Refuses to send anything without a `contact`, and
+/// fails with `HttpError::CurlMissing` before trying when curl is not installed.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct HttpClient {
+    pub base_url: String,
+    pub session: Option<String>,
+    pub contact: Option<String>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct HttpResponse {
+    pub status: u32,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+impl HttpClient {
+    pub fn new(base_url: &str, session: Option<String>, contact: Option<String>) -> Self {
+        HttpClient { base_url: base_url.trim_end_matches('/').to_owned(), session, contact }
+    }
+
+    pub fn get(&self, path: &str) -> Result<HttpResponse, HttpError> {
+        self.send(path, &[])
+    }
+
+    /// Posts `fields` as `application/x-www-form-urlencoded`.
+    pub fn post_form(&self, path: &str, fields: &[(&str, &str)]) -> Result<HttpResponse, HttpError> {
+        let args = fields.iter()
+            .flat_map(|(k, v)| ["--data-urlencode".to_owned(), format!("{}={}", k, v)])
+            .collect::<Vec<_>>();
+        self.send(path, &args)
+    }
+
+    fn send(&self, path: &str, args: &[String]) -> Result<HttpResponse, HttpError> {
+        let contact = self.contact.as_deref().ok_or(HttpError::NoContact)?;
+        if !curl_available() {
+            return Err(HttpError::CurlMissing);
+        }
+        let url = format!("{}{}", self.base_url, path);
+
+        // Headers are passed on stdin so the session token stays out of the process list.
+        let mut child = Command::new("curl")
+            .args(["--silent", "--show-error", "--location", "--max-time", "30"])
+            .args(["--user-agent", &user_agent(contact), "--header", "@-"])
+            .args(["--write-out", "\n%{http_code}"])
+            .args(args)
+            .arg(&url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| match e.kind() {
+                ErrorKind::NotFound => HttpError::CurlMissing,
+                _ => HttpError::Failed(format!("Unable to run curl: {}", e)),
+            })?;
+
+        let headers = match &self.session {
+            Some(session) => format!("Cookie: session={}\n", session),
+            None => String::new(),
+        };
+        child.stdin.take()
+            .ok_or_else(|| HttpError::Failed("Unable to write to curl".to_owned()))?
+            .write_all(headers.as_bytes())
+            .map_err(|e| HttpError::Failed(format!("Unable to write to curl: {}", e)))?;
+
+        let failed = |e: String| HttpError::Failed(format!("{}: {}", url, e));
+        let output = child.wait_with_output().map_err(|e| failed(format!("curl failed: {}", e)))?;
+        if !output.status.success() {
+            return Err(failed(String::from_utf8_lossy(&output.stderr).trim().to_owned()));
+        }
+
+        let stdout = String::from_utf8(output.stdout).map_err(|_| failed("response is not UTF-8".to_owned()))?;
+        let (body, status) = stdout.rsplit_once('\n').ok_or_else(|| failed("no status from curl".to_owned()))?;
+        let status = status.trim().parse::<u32>().map_err(|_| failed(format!("bad status '{}'", status)))?;
+
+        Ok(HttpResponse { status, body: body.to_owned() })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read};
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+
+    /// Serves each of `responses` to one request in turn, returning the raw requests.
+    pub(crate) fn serve(responses: Vec<(u32, String)>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = std::thread::spawn(move || {
+            let mut requests = vec![];
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request = String::new();
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(n) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        length = n.trim().parse::<usize>().unwrap();
+                    }
+                    request += &line;
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                let mut content = vec![0; length];
+                reader.read_exact(&mut content).unwrap();
+                request += &String::from_utf8(content).unwrap();
+                requests.push(request);
+
+                let response = format!("HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body);
+                reader.into_inner().write_all(response.as_bytes()).unwrap();
+            }
+            requests
+        });
+
+        (url, handle)
+    }
+
+    #[test]
+    fn get_and_post() {
+        let (url, server) = serve(vec![(200, "1 2\n3 4\n".to_owned()), (404, "nope".to_owned())]);
+        let client = HttpClient::new(&format!("{}/", url), Some("abc".to_owned()), Some("me@example.com".to_owned()));
+
+        let response = client.get("/2024/day/1/input").unwrap();
+        assert_eq!(response, HttpResponse { status: 200, body: "1 2\n3 4\n".to_owned() });
+        assert!(response.is_success());
+
+        let response = client.post_form("/2024/day/1/answer", &[("level", "1"), ("answer", "a b")]).unwrap();
+        assert_eq!(response.status, 404);
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2024/day/1/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("Cookie: session=abc\r\n"));
+        assert!(requests[0].contains("User-Agent: advent2024 input cache and submit client (me@example.com)\r\n"));
+        assert!(requests[1].starts_with("POST /2024/day/1/answer "));
+        assert!(requests[1].ends_with("\r\n\r\nlevel=1&answer=a+b"));
+
+        let anonymous = HttpClient::new(&url, None, None);
+        assert_eq!(anonymous.get("/"), Err(HttpError::NoContact));
+        assert!(String::from(HttpError::NoContact).contains(CONTACT_VAR));
+    }
+}
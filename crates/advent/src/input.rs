@@ -0,0 +1,150 @@
+use crate::store::{fetch_enabled, InputStore, FETCH_VAR, YEAR};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// Where a day's puzzle input is read from at runtime.
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub enum InputSource {
+    File(PathBuf),
+    Stdin,
+    Dir(PathBuf),
+    /// The input compiled in with `include_input!`, or from the `InputStore` if that is empty.
+    /// The store only downloads a missing input when `AOC_FETCH` is set.
+    #[default]
+    Embedded,
+}
+
+impl InputSource {
+    /// Uses `AOC_INPUT_DIR` when it is set, otherwise falls back to the embedded input.
+    pub fn from_env() -> Self {
+        match std::env::var_os(INPUT_DIR_VAR) {
+            Some(dir) if !dir.is_empty() => InputSource::Dir(PathBuf::from(dir)),
+            _ => InputSource::Embedded,
+        }
+    }
+
+    /// Parses `--input <path|->` and `--input-dir <dir>` out of `args`,
+    /// returning the chosen source and any remaining arguments.
+    pub fn from_args<I>(args: I) -> Result<(Self, Vec<String>), String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut source = None;
+        let mut rest = vec![];
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let next = match arg.as_str() {
+                "--input" | "-i" | "--input-dir" => args.next()
+                    .ok_or_else(|| format!("Missing value for {}", arg))?,
+                _ => {
+                    rest.push(arg);
+                    continue;
+                }
+            };
+            if source.is_some() {
+                return Err("Only one of --input or --input-dir may be given".to_string());
+            }
+            source = Some(match (arg.as_str(), next.as_str()) {
+                ("--input-dir", _) => InputSource::Dir(PathBuf::from(next)),
+                (_, "-") => InputSource::Stdin,
+                _ => InputSource::File(PathBuf::from(next)),
+            });
+        }
+
+        Ok((source.unwrap_or_else(InputSource::from_env), rest))
+    }
+
+    /// Name used to key recorded answers: the file stem for `--input`, otherwise `input`.
+    pub fn name(&self) -> String {
+        match self {
+            InputSource::File(path) => path.file_stem()
+                .map(|x| x.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            InputSource::Stdin => "stdin".to_owned(),
+            InputSource::Dir(_) | InputSource::Embedded => "input".to_owned(),
+        }
+    }
+
+    pub fn load(&self, day: u32, embedded: fn() -> &'static str) -> io::Result<String> {
+        match self {
+            InputSource::File(path) => read_file(path),
+            InputSource::Stdin => {
+                let mut s = String::new();
+                io::stdin().read_to_string(&mut s)?;
+                Ok(s)
+            },
+            InputSource::Dir(dir) => read_file(&dir.join(input_file_name(day))),
+            InputSource::Embedded => match embedded() {
+                s if s.trim().is_empty() => load_stored(day),
+                s => Ok(s.to_owned()),
+            },
+        }
+    }
+}
+
+fn load_stored(day: u32) -> io::Result<String> {
+    let store = InputStore::from_env();
+    let result = match fetch_enabled() {
+        true => store.get(YEAR, day),
+        false => store.cached(YEAR, day).ok_or_else(|| format!(
+            "src/input/{} is empty and {} is not cached; set {}=1 to download it",
+            input_file_name(day), store.path(YEAR, day).display(), FETCH_VAR)),
+    };
+    result.map_err(|e| io::Error::new(io::ErrorKind::NotFound, e))
+}
+
+pub fn input_file_name(day: u32) -> String {
+    format!("{:02}.txt", day)
+}
+
+fn read_file(path: &Path) -> io::Result<String> {
+    std::fs::read_to_string(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|x| x.to_owned()).collect()
+    }
+
+    #[test]
+    fn source_from_args() {
+        let (source, rest) = InputSource::from_args(args("6 --input a.txt")).unwrap();
+        assert_eq!(source, InputSource::File(PathBuf::from("a.txt")));
+        assert_eq!(source.name(), "a");
+        assert_eq!(rest, vec!["6"]);
+
+        let (source, _) = InputSource::from_args(args("-i -")).unwrap();
+        assert_eq!(source, InputSource::Stdin);
+
+        let (source, rest) = InputSource::from_args(args("--input-dir inputs 1..3")).unwrap();
+        assert_eq!(source, InputSource::Dir(PathBuf::from("inputs")));
+        assert_eq!(rest, vec!["1..3"]);
+
+        assert!(InputSource::from_args(args("--input")).is_err());
+        assert!(InputSource::from_args(args("--input a --input-dir b")).is_err());
+    }
+
+    #[test]
+    fn load_from_dir() {
+        let dir = std::env::temp_dir().join(format!("advent-input-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("07.txt"), "from dir").unwrap();
+
+        let source = InputSource::Dir(dir.clone());
+        assert_eq!(source.load(7, || "embedded").unwrap(), "from dir");
+        assert!(source.load(8, || "embedded").is_err());
+
+        let source = InputSource::File(dir.join("07.txt"));
+        assert_eq!(source.load(1, || "embedded").unwrap(), "from dir");
+        assert_eq!(InputSource::Embedded.load(7, || "embedded").unwrap(), "embedded");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
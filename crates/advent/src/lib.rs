@@ -1,7 +1,40 @@
-
+pub mod answer;
+pub mod answers;
+pub mod bench;
+pub mod board;
+pub mod cycle;
+pub mod examples;
+pub mod http;
+pub mod input;
+pub mod parallel;
+pub mod parse;
+pub mod scaffold;
+pub mod search;
 pub mod shared;
-use std::cmp::Ordering;
+pub mod solution;
+pub mod store;
+pub mod submit;
+pub mod visited;
+
+pub use answer::*;
+pub use answers::*;
+pub use bench::*;
+pub use board::*;
+pub use cycle::*;
+pub use examples::*;
+pub use input::*;
+pub use parallel::*;
+pub use parse::*;
+pub use search::*;
+pub use solution::*;
+pub use store::*;
+pub use submit::*;
+pub use visited::*;
+
+use std::ops::{Add, Mul, Neg, Sub};
 
+/// Embeds `src/input/NN.txt`, used when no input is supplied at runtime.
+/// An empty file (as left by `new-day`) falls back to the `InputStore`.
 #[macro_export]
 macro_rules! include_input {
     ($day:literal) => {
@@ -9,16 +42,91 @@ macro_rules! include_input {
     };
 }
 
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+pub enum LineEnding {
+    /// Accepts `\n`, `\r\n` or a mix of both.
+    #[default]
+    Any,
+    /// Splits on `\r\n` only.
+    CrLf,
+}
+
+/// Splits into lines using LF, CRLF or mixed line endings, ignoring a trailing newline.
 pub fn input_as_lines(s: &str) -> Vec<String> {
-    s.split("\r\n").map(|x| x.to_owned()).collect::<Vec<_>>()
+    split_lines(s, LineEnding::Any, true)
+}
+
+/// Splits strictly on `\r\n`, keeping an empty last line for a trailing newline.
+pub fn input_as_lines_strict(s: &str) -> Vec<String> {
+    split_lines(s, LineEnding::CrLf, false)
+}
+
+pub fn split_lines(s: &str, ending: LineEnding, trim_trailing: bool) -> Vec<String> {
+    let s = match (trim_trailing, ending) {
+        (false, _) => s,
+        (true, LineEnding::Any) => s.strip_suffix('\n').map(|x| x.strip_suffix('\r').unwrap_or(x)).unwrap_or(s),
+        (true, LineEnding::CrLf) => s.strip_suffix("\r\n").unwrap_or(s),
+    };
+
+    match ending {
+        LineEnding::Any => s.split('\n')
+            .map(|x| x.strip_suffix('\r').unwrap_or(x).to_owned())
+            .collect::<Vec<_>>(),
+        LineEnding::CrLf => s.split("\r\n").map(|x| x.to_owned()).collect::<Vec<_>>(),
+    }
+}
+
+/// A run of non-blank lines from the puzzle input.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Section {
+    /// 1-based line number of the first line in the input.
+    pub line: usize,
+    pub lines: Vec<String>,
+}
+
+impl Section {
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// Parses each line with `f`, reporting errors at their line in the whole input.
+    pub fn parse_lines<T, F>(&self, f: F) -> Result<Vec<T>, ParseError>
+    where
+        F: Fn(&str) -> Result<T, ParseError>,
+    {
+        self.lines.iter()
+            .enumerate()
+            .map(|(i, line)| f(line).map_err(|e| e.with_line(self.line + i)))
+            .collect()
+    }
+}
+
+/// Splits into sections separated by one or more blank lines, accepting any line ending.
+pub fn input_as_sections(s: &str) -> Vec<Section> {
+    let mut sections: Vec<Section> = vec![];
+    let mut blank = true;
+
+    for (i, line) in input_as_lines(s).into_iter().enumerate() {
+        if line.trim().is_empty() {
+            blank = true;
+        } else if blank {
+            sections.push(Section { line: i + 1, lines: vec![line] });
+            blank = false;
+        } else if let Some(section) = sections.last_mut() {
+            section.lines.push(line);
+        }
+    }
+
+    sections
 }
 
-pub fn input_as_ints(s: &str) -> Vec<i64> {
-    let input_lines = input_as_lines(s);
+/// Rewrites every `\r\n` as `\n`.
+pub fn normalize_line_endings(s: &str) -> String {
+    s.replace("\r\n", "\n")
+}
 
-    input_lines.iter()
-        .map(|s| s.parse::<i64>().expect("Unable to convert line to i64"))
-        .collect()
+pub fn input_as_ints(s: &str) -> Result<Vec<i64>, ParseError> {
+    parse_lines(s, |line| parse_token::<i64>(line, line.trim()))
 }
 
 #[derive(Debug, Eq, PartialEq, Hash, Copy, Clone, Default)]
@@ -27,6 +135,57 @@ pub struct Point32 {
     pub y: i32,
 }
 
+impl Point32 {
+    pub fn new(x: i32, y: i32) -> Self {
+        Point32 { x, y }
+    }
+
+    pub fn manhattan(&self, other: Point32) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    pub fn chebyshev(&self, other: Point32) -> i32 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    /// The point `n` steps away in direction `dir`.
+    pub fn step(&self, dir: Compass, n: i32) -> Self {
+        *self + dir.delta() * n
+    }
+}
+
+impl Add for Point32 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Point32 { x: self.x + rhs.x, y: self.y + rhs.y }
+    }
+}
+
+impl Sub for Point32 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Point32 { x: self.x - rhs.x, y: self.y - rhs.y }
+    }
+}
+
+impl Mul<i32> for Point32 {
+    type Output = Self;
+
+    fn mul(self, rhs: i32) -> Self {
+        Point32 { x: self.x * rhs, y: self.y * rhs }
+    }
+}
+
+impl Neg for Point32 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point32 { x: -self.x, y: -self.y }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Hash, Copy, Clone, Default)]
 pub enum Compass {
     #[default]
@@ -41,38 +200,66 @@ pub enum Compass {
 }
 
 impl Compass {
+    pub fn all() -> [Compass; 8] {
+        [
+            Compass::North, Compass::NorthEast, Compass::East, Compass::SouthEast,
+            Compass::South, Compass::SouthWest, Compass::West, Compass::NorthWest,
+        ]
+    }
+
+    pub fn cardinals() -> [Compass; 4] {
+        [Compass::North, Compass::East, Compass::South, Compass::West]
+    }
+
+    /// The unit step for this direction, with y increasing southwards.
+    pub fn delta(&self) -> Point32 {
+        let (x, y) = match self {
+            Compass::North => (0, -1),
+            Compass::NorthEast => (1, -1),
+            Compass::East => (1, 0),
+            Compass::SouthEast => (1, 1),
+            Compass::South => (0, 1),
+            Compass::SouthWest => (-1, 1),
+            Compass::West => (-1, 0),
+            Compass::NorthWest => (-1, -1),
+        };
+        Point32 { x, y }
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.turn_right(4)
+    }
+
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// The direction `i` eighth-turns clockwise from north; any `i` is accepted.
+    pub fn from_index(i: i64) -> Self {
+        Compass::all()[i.rem_euclid(8) as usize]
+    }
+
+    /// Rotates clockwise by `steps` of 45°, or anticlockwise for negative `steps`.
+    pub fn rotate(&self, steps: i32) -> Self {
+        Compass::from_index(self.index() as i64 + steps as i64)
+    }
+
     pub fn turn_left(&self, times: usize) -> Self {
-        match times.cmp(&1) {
-            Ordering::Greater => self.turn_left(times - 1).turn_left(1),
-            Ordering::Equal => match self {
-                Compass::North => Compass::NorthWest,
-                Compass::NorthEast => Compass::North,
-                Compass::East => Compass::NorthEast,
-                Compass::SouthEast => Compass::East,
-                Compass::South => Compass::SouthEast,
-                Compass::SouthWest => Compass::South,
-                Compass::West => Compass::SouthWest,
-                Compass::NorthWest => Compass::West,
-            },
-            Ordering::Less => *self,
-        }
+        self.rotate(-((times % 8) as i32))
     }
 
     pub fn turn_right(&self, times: usize) -> Self {
-        match times.cmp(&1) {
-            Ordering::Greater => self.turn_right(times - 1).turn_right(1),
-            Ordering::Equal => match self {
-                Compass::North => Compass::NorthEast,
-                Compass::NorthEast => Compass::East,
-                Compass::East => Compass::SouthEast,
-                Compass::SouthEast => Compass::South,
-                Compass::South => Compass::SouthWest,
-                Compass::SouthWest => Compass::West,
-                Compass::West => Compass::NorthWest,
-                Compass::NorthWest => Compass::North,
-            },
-            Ordering::Less => *self,
-        }
+        self.rotate((times % 8) as i32)
+    }
+
+    /// Degrees clockwise from north, in `0..360`.
+    pub fn to_degrees(&self) -> i32 {
+        self.index() as i32 * 45
+    }
+
+    /// The direction for a bearing in degrees, if it is a multiple of 45°.
+    pub fn from_degrees(degrees: i32) -> Option<Self> {
+        (degrees % 45 == 0).then(|| Compass::from_index((degrees / 45) as i64))
     }
 
     pub fn cardinal_left(&self) -> Self {
@@ -83,28 +270,98 @@ impl Compass {
     }
 }
 
+impl TryFrom<char> for Compass {
+    type Error = ParseError;
 
-#[derive(Debug)]
-pub struct Board2D<T> {
-    vec: Vec<T>,
-    width: usize,
-    height: usize,
+    /// Accepts arrows `^ > v <` and letters `N E S W`.
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '^' | 'N' | 'n' => Ok(Compass::North),
+            '>' | 'E' | 'e' => Ok(Compass::East),
+            'v' | 'V' | 'S' | 's' => Ok(Compass::South),
+            '<' | 'W' | 'w' => Ok(Compass::West),
+            _ => Err(ParseError::new("Expected a direction", &c.to_string())),
+        }
+    }
 }
 
-impl<T> Board2D<T> {
-    pub fn new(vec: Vec<T>, height: usize, width: usize) -> Self {
-        assert!(vec.len() == width * height);
-        Self { vec, width, height }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_any_ending() {
+        assert_eq!(input_as_lines("a\nb\nc"), vec!["a", "b", "c"]);
+        assert_eq!(input_as_lines("a\r\nb\r\nc\r\n"), vec!["a", "b", "c"]);
+        assert_eq!(input_as_lines("a\r\nb\nc\n"), vec!["a", "b", "c"]);
+        assert_eq!(input_as_lines("a\n\nb\n\n"), vec!["a", "", "b", ""]);
+        assert_eq!(split_lines("a\nb\n", LineEnding::Any, false), vec!["a", "b", ""]);
     }
 
-    pub fn width(&self) -> usize { self.width }
-    pub fn height(&self) -> usize { self.height }
+    #[test]
+    fn sections() {
+        let sections = input_as_sections("a\r\nb\r\n\r\nc\n\n\n  \nd\ne\n\n");
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[0], Section { line: 1, lines: vec!["a".to_owned(), "b".to_owned()] });
+        assert_eq!(sections[1].line, 4);
+        assert_eq!(sections[2].line, 8);
+        assert_eq!(sections[2].text(), "d\ne");
 
-    pub fn index(&self, x: i64, y: i64) -> Option<&T> {
-        if y >= 0 && x >= 0 && (y as usize) < self.height && (x as usize) < self.width  {
-            Some(&self.vec[((y as usize) * self.width) + (x as usize)])
-        } else {
-            None
-        }
+        let err = sections[2].parse_lines(|x| parse_token::<i64>(x, x)).unwrap_err();
+        assert_eq!(err.line, 8);
+        assert!(input_as_sections("\n\n").is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn point_arithmetic() {
+        let a = Point32::new(1, 2);
+        let b = Point32::new(4, -2);
+        assert_eq!(a + b, Point32::new(5, 0));
+        assert_eq!(a - b, Point32::new(-3, 4));
+        assert_eq!(a * 3, Point32::new(3, 6));
+        assert_eq!(-a, Point32::new(-1, -2));
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!(a.step(Compass::North, 2), Point32::new(1, 0));
+        assert_eq!(a.step(Compass::SouthWest, 1), Point32::new(0, 3));
+    }
+
+    #[test]
+    fn compass_delta() {
+        assert!(Compass::all().iter().all(|d| d.delta() + d.opposite().delta() == Point32::default()));
+        assert!(Compass::cardinals().iter().all(|d| d.delta().manhattan(Point32::default()) == 1));
+        assert_eq!(Compass::NorthEast.delta(), Point32::new(1, -1));
+        assert_eq!(Compass::West.opposite(), Compass::East);
+    }
+
+    #[test]
+    fn compass_rotate() {
+        assert_eq!(Compass::North.turn_right(1_000_000), Compass::North);
+        assert_eq!(Compass::North.turn_left(1_000_001), Compass::NorthWest);
+        assert_eq!(Compass::East.rotate(-3), Compass::NorthWest);
+        assert_eq!(Compass::East.rotate(i32::MIN), Compass::East);
+        assert_eq!(Compass::West.cardinal_right(), Compass::North);
+        assert_eq!(Compass::West.cardinal_left(), Compass::South);
+        assert_eq!(Compass::SouthWest.to_degrees(), 225);
+        assert_eq!(Compass::from_degrees(-90), Some(Compass::West));
+        assert_eq!(Compass::from_degrees(720), Some(Compass::North));
+        assert_eq!(Compass::from_degrees(100), None);
+    }
+
+    #[test]
+    fn compass_from_char() {
+        assert_eq!(Compass::try_from('^'), Ok(Compass::North));
+        assert_eq!(Compass::try_from('>'), Ok(Compass::East));
+        assert_eq!(Compass::try_from('v'), Ok(Compass::South));
+        assert_eq!(Compass::try_from('W'), Ok(Compass::West));
+        assert!(Compass::try_from('x').is_err());
+    }
+
+    #[test]
+    fn lines_strict() {
+        assert_eq!(input_as_lines_strict("a\r\nb\r\n"), vec!["a", "b", ""]);
+        assert_eq!(input_as_lines_strict("a\nb"), vec!["a\nb"]);
+        assert_eq!(split_lines("a\r\nb\r\n", LineEnding::CrLf, true), vec!["a", "b"]);
+        assert_eq!(normalize_line_endings("a\r\nb\n"), "a\nb\n");
+    }
+}
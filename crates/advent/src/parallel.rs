@@ -0,0 +1,165 @@
+use crate::answer::Answer;
+use crate::parse::ParseError;
+use crate::solution::Solution;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Both answers for a day with the time taken by each step. `total` is wall time,
+/// so it is less than the sum of the steps when the parts ran in parallel.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DayResult {
+    pub day: u32,
+    pub part1: Answer,
+    pub part2: Answer,
+    pub parse_time: Duration,
+    pub part1_time: Duration,
+    pub part2_time: Duration,
+    pub total_time: Duration,
+}
+
+/// Parses once, then solves both parts, on separate threads when `parallel` is set.
+pub fn solve_timed<S: Solution>(s: &str, parallel: bool) -> Result<DayResult, ParseError> {
+    let start = Instant::now();
+    let model = S::parse(s)?;
+    let parse_time = start.elapsed();
+
+    let part1 = || timed(|| S::part1(&model).into());
+    let part2 = || timed(|| S::part2(&model).into());
+    let ((part1, part1_time), (part2, part2_time)) = match parallel {
+        true => std::thread::scope(|scope| {
+            let handle = scope.spawn(part1);
+            let part2 = part2();
+            (handle.join().unwrap_or_else(|e| std::panic::resume_unwind(e)), part2)
+        }),
+        false => (part1(), part2()),
+    };
+
+    Ok(DayResult { day: S::DAY, part1, part2, parse_time, part1_time, part2_time, total_time: start.elapsed() })
+}
+
+fn timed<F: FnOnce() -> Answer>(f: F) -> (Answer, Duration) {
+    let start = Instant::now();
+    let answer = f();
+    (answer, start.elapsed())
+}
+
+/// Maps `f` over `items` on up to `jobs` threads, returning the results in the order of `items`.
+pub fn map_parallel<T, R, F>(items: Vec<T>, jobs: usize, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let count = items.len();
+    let items = items.into_iter().map(|x| Mutex::new(Some(x))).collect::<Vec<_>>();
+    let results = (0..count).map(|_| Mutex::new(None)).collect::<Vec<_>>();
+    let next = AtomicUsize::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, count.max(1)) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= count {
+                    break;
+                }
+                let item = items[i].lock().unwrap().take().expect("each item is taken once");
+                *results[i].lock().unwrap() = Some(f(item));
+            });
+        }
+    });
+
+    results.into_iter()
+        .map(|x| x.into_inner().unwrap().expect("every item has a result"))
+        .collect()
+}
+
+/// Parses `--parallel` (one job per CPU) and `--jobs <n>` / `-j <n>` out of `args`.
+/// Returns the number of jobs, 1 when neither is given, and the remaining arguments.
+pub fn jobs_from_args<I>(args: I) -> Result<(usize, Vec<String>), String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut jobs = 1;
+    let mut rest = vec![];
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--parallel" => jobs = std::thread::available_parallelism().map(|x| x.get()).unwrap_or(1),
+            "--jobs" | "-j" => {
+                let value = args.next().ok_or_else(|| format!("Missing value for {}", arg))?;
+                jobs = value.parse::<usize>().ok().filter(|x| *x > 0)
+                    .ok_or_else(|| format!("Invalid job count: {}", value))?;
+            },
+            _ => rest.push(arg),
+        }
+    }
+
+    Ok((jobs, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Slow;
+
+    impl Solution for Slow {
+        const DAY: u32 = 3;
+
+        type Model = u64;
+        type Answer1 = u64;
+        type Answer2 = u64;
+
+        fn default_input() -> &'static str {
+            "50"
+        }
+
+        fn parse(s: &str) -> Result<u64, ParseError> {
+            crate::parse_token(s, s)
+        }
+
+        fn part1(ms: &u64) -> u64 {
+            std::thread::sleep(Duration::from_millis(*ms));
+            1
+        }
+
+        fn part2(ms: &u64) -> u64 {
+            std::thread::sleep(Duration::from_millis(*ms));
+            2
+        }
+    }
+
+    #[test]
+    fn ordered_results() {
+        let items = (0..20u64).collect::<Vec<_>>();
+        let results = map_parallel(items, 4, |x| {
+            std::thread::sleep(Duration::from_millis(20 - x));
+            x * 2
+        });
+        assert_eq!(results, (0..20).map(|x| x * 2).collect::<Vec<_>>());
+        assert!(map_parallel(Vec::<u32>::new(), 4, |x| x).is_empty());
+    }
+
+    #[test]
+    fn parts_in_parallel() {
+        let r = solve_timed::<Slow>("50", true).unwrap();
+        assert_eq!((r.day, r.part1.clone(), r.part2.clone()), (3, Answer::Unsigned(1), Answer::Unsigned(2)));
+        assert!(r.part1_time >= Duration::from_millis(50));
+        assert!(r.total_time < r.part1_time + r.part2_time);
+
+        let r = solve_timed::<Slow>("5", false).unwrap();
+        assert!(r.total_time >= r.part1_time + r.part2_time);
+        assert!(solve_timed::<Slow>("x", true).is_err());
+    }
+
+    #[test]
+    fn jobs() {
+        let args = |s: &str| s.split_whitespace().map(|x| x.to_owned()).collect::<Vec<_>>();
+        assert_eq!(jobs_from_args(args("6")).unwrap(), (1, args("6")));
+        assert_eq!(jobs_from_args(args("-j 4 all")).unwrap(), (4, args("all")));
+        assert!(jobs_from_args(args("--parallel")).unwrap().0 >= 1);
+        assert!(jobs_from_args(args("--jobs 0")).is_err());
+    }
+}
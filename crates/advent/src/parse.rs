@@ -0,0 +1,122 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A parse failure pointing at the offending text in the puzzle input.
+///
+/// `line` and `column` are 1-based; zero means the position is not known,
+/// e.g. a single-line parser leaves `line` for its caller to fill in.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(message: impl Into<String>, text: &str) -> Self {
+        ParseError { line: 0, column: 0, text: text.to_owned(), message: message.into() }
+    }
+
+    /// Builds an error for `token`, a slice of (or substring within) `line`.
+    pub fn at_token(message: impl Into<String>, line: &str, token: &str) -> Self {
+        ParseError::new(message, token).with_column(column_of(line, token))
+    }
+
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+
+    pub fn with_column(mut self, column: usize) -> Self {
+        self.column = column;
+        self
+    }
+
+    /// Renders the error with the offending input line and a caret under the column.
+    pub fn diagnostic(&self, input: &str) -> String {
+        let mut s = format!("error: {}", self);
+        let line = (self.line > 0).then(|| input.lines().nth(self.line - 1)).flatten();
+        if let Some(src) = line {
+            let gutter = self.line.to_string().len();
+            let src = src.trim_end_matches('\r');
+            s += &format!("\n{:>w$} |\n{} | {}", "", self.line, src, w = gutter);
+            if self.column > 0 {
+                let width = self.text.chars().count().max(1);
+                s += &format!("\n{:>w$} | {}{}", "", " ".repeat(self.column - 1), "^".repeat(width), w = gutter);
+            }
+        }
+        s
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (0, 0) => write!(f, "{}", self.message)?,
+            (0, c) => write!(f, "column {}: {}", c, self.message)?,
+            (l, 0) => write!(f, "line {}: {}", l, self.message)?,
+            (l, c) => write!(f, "line {}, column {}: {}", l, c, self.message)?,
+        }
+        if !self.text.is_empty() {
+            write!(f, " ('{}')", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses `token`, a slice of `line`, reporting its column on failure.
+pub fn parse_token<T: FromStr>(line: &str, token: &str) -> Result<T, ParseError> {
+    token.parse::<T>()
+        .map_err(|_| ParseError::at_token(format!("Unable to parse {}", std::any::type_name::<T>()), line, token))
+}
+
+/// Parses each line with `f`, filling in the 1-based line number of any error.
+pub fn parse_lines<T, F>(s: &str, f: F) -> Result<Vec<T>, ParseError>
+where
+    F: Fn(&str) -> Result<T, ParseError>,
+{
+    crate::input_as_lines(s).iter()
+        .enumerate()
+        .map(|(i, line)| f(line).map_err(|e| e.with_line(i + 1)))
+        .collect()
+}
+
+fn column_of(line: &str, token: &str) -> usize {
+    let start = line.as_ptr() as usize;
+    let pos = token.as_ptr() as usize;
+    if pos >= start && pos + token.len() <= start + line.len() {
+        line[..pos - start].chars().count() + 1
+    } else {
+        line.find(token).map(|i| line[..i].chars().count() + 1).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_column() {
+        let line = "12 x4 7";
+        let err = parse_token::<i64>(line, &line[3..5]).unwrap_err();
+        assert_eq!(err.column, 4);
+        assert_eq!(err.text, "x4");
+        assert_eq!(ParseError::at_token("bad", line, "7").column, 7);
+        assert_eq!(ParseError::at_token("bad", line, "zz").column, 0);
+    }
+
+    #[test]
+    fn lines_and_diagnostic() {
+        let input = "1 2\n3 x4\n5 6";
+        let err = parse_lines(input, |line| {
+            line.split(' ').map(|x| parse_token::<i64>(line, x)).collect::<Result<Vec<_>, _>>()
+        }).unwrap_err();
+
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.to_string(), "line 2, column 3: Unable to parse i64 ('x4')");
+        assert_eq!(err.diagnostic(input), "error: line 2, column 3: Unable to parse i64 ('x4')\n  |\n2 | 3 x4\n  |   ^^");
+    }
+}
@@ -0,0 +1,157 @@
+use crate::input_file_name;
+use std::path::Path;
+
+/// Creates the files and registrations for a new day under the package `root`:
+///
+/// * `src/NN.rs` from `src/template.rs`;
+/// * the `[[bin]] dayNN` block in `Cargo.toml`, uncommenting it if present;
+/// * the day's module and `Registry` entry in `src/aoc.rs`;
+/// * empty `src/input/NN.txt` and `src/problem/NN.txt`, unless they already exist.
+///
+/// Nothing is written if the day's source file or binary already exists.
+/// Returns a description of each step taken.
+pub fn new_day(root: &Path, day: u32) -> Result<Vec<String>, String> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("Day must be between 1 and 25, found {}", day));
+    }
+
+    let source = root.join("src").join(format!("{:02}.rs", day));
+    if source.exists() {
+        return Err(format!("{} already exists", source.display()));
+    }
+
+    let manifest_path = root.join("Cargo.toml");
+    let aoc_path = root.join("src/aoc.rs");
+    let template = read_file(&root.join("src/template.rs"))?;
+    let manifest = enable_bin(&read_file(&manifest_path)?, day)?;
+    let aoc = register_day(&read_file(&aoc_path)?, day)?;
+
+    let mut steps = vec![];
+    write_file(&source, &render_template(&template, day))?;
+    steps.push(format!("Created {}", source.display()));
+    write_file(&manifest_path, &manifest)?;
+    steps.push(format!("Enabled day{:02} in {}", day, manifest_path.display()));
+    write_file(&aoc_path, &aoc)?;
+    steps.push(format!("Registered day{:02} in {}", day, aoc_path.display()));
+
+    for dir in ["src/input", "src/problem"] {
+        let path = root.join(dir).join(input_file_name(day));
+        if path.exists() {
+            steps.push(format!("Kept existing {}", path.display()));
+        } else {
+            write_file(&path, "")?;
+            steps.push(format!("Created {}", path.display()));
+        }
+    }
+
+    Ok(steps)
+}
+
+/// The template with `Day00` and its day number replaced by `day`.
+pub fn render_template(template: &str, day: u32) -> String {
+    template
+        .replace("Day00", &format!("Day{:02}", day))
+        .replace("const DAY: u32 = 0;", &format!("const DAY: u32 = {};", day))
+        .replace("include_input!(00)", &format!("include_input!({:02})", day))
+}
+
+/// Uncomments the day's `[[bin]]` block in `Cargo.toml`, or appends one if there is none.
+pub fn enable_bin(manifest: &str, day: u32) -> Result<String, String> {
+    let block = format!("[[bin]]\nname = \"day{:02}\"\npath = \"src/{:02}.rs\"\n", day, day);
+    let commented = format!("#{}\n", block.trim_end().replace('\n', "\n#"));
+    let manifest = crate::normalize_line_endings(manifest);
+
+    if manifest.contains(&block) {
+        return Err(format!("day{:02} is already enabled in Cargo.toml", day));
+    }
+    if manifest.contains(&commented) {
+        return Ok(manifest.replacen(&commented, &block, 1));
+    }
+
+    let sep = if manifest.ends_with('\n') { "\n" } else { "\n\n" };
+    Ok(format!("{}{}{}", manifest, sep, block))
+}
+
+/// Adds the day's module and `.with::<DayNN>()` entry to `src/aoc.rs`,
+/// after the last existing module and entry.
+pub fn register_day(aoc: &str, day: u32) -> Result<String, String> {
+    let module = format!("#[path = \"{:02}.rs\"] mod day{:02};", day, day);
+    let entry = format!("        .with::<day{:02}::Day{:02}>()", day, day);
+    if aoc.contains(&module) {
+        return Err(format!("day{:02} is already registered in src/aoc.rs", day));
+    }
+
+    let mut lines = crate::input_as_lines(aoc);
+    let last_module = lines.iter().rposition(|x| x.starts_with("#[path = "))
+        .ok_or("No day modules found in src/aoc.rs")?;
+    lines.insert(last_module + 1, module);
+    let last_entry = lines.iter().rposition(|x| x.trim_start().starts_with(".with::<"))
+        .ok_or("No registry entries found in src/aoc.rs")?;
+    lines.insert(last_entry + 1, entry);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+fn read_file(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn write_file(path: &Path, s: &str) -> Result<(), String> {
+    std::fs::write(path, s).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AOC: &str = "use advent::*;\n\n#[path = \"01.rs\"] mod day01;\n\nfn registry() -> Registry {\n    Registry::default()\n        .with::<day01::Day01>()\n}\n";
+
+    #[test]
+    fn render() {
+        let s = render_template("pub struct Day00;\nconst DAY: u32 = 0;\ninclude_input!(00)\nanswer_tests!(Day00);", 9);
+        assert_eq!(s, "pub struct Day09;\nconst DAY: u32 = 9;\ninclude_input!(09)\nanswer_tests!(Day09);");
+    }
+
+    #[test]
+    fn bin_blocks() {
+        let manifest = "[[bin]]\nname = \"day01\"\npath = \"src/01.rs\"\n\n#[[bin]]\n#name = \"day02\"\n#path = \"src/02.rs\"\n";
+        let s = enable_bin(manifest, 2).unwrap();
+        assert!(s.ends_with("\n\n[[bin]]\nname = \"day02\"\npath = \"src/02.rs\"\n"));
+        assert!(enable_bin(&s, 2).is_err());
+
+        let s = enable_bin(manifest, 3).unwrap();
+        assert!(s.ends_with("#path = \"src/02.rs\"\n\n[[bin]]\nname = \"day03\"\npath = \"src/03.rs\"\n"));
+    }
+
+    #[test]
+    fn registration() {
+        let s = register_day(AOC, 2).unwrap();
+        assert!(s.contains("mod day01;\n#[path = \"02.rs\"] mod day02;\n"));
+        assert!(s.contains(".with::<day01::Day01>()\n        .with::<day02::Day02>()\n}"));
+        assert!(register_day(&s, 2).is_err());
+        assert!(register_day("fn main() {}", 2).is_err());
+    }
+
+    #[test]
+    fn scaffold() {
+        let root = std::env::temp_dir().join(format!("advent-scaffold-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src/input")).unwrap();
+        std::fs::create_dir_all(root.join("src/problem")).unwrap();
+        std::fs::write(root.join("src/template.rs"), "pub struct Day00;").unwrap();
+        std::fs::write(root.join("src/aoc.rs"), AOC).unwrap();
+        std::fs::write(root.join("Cargo.toml"), "#[[bin]]\n#name = \"day02\"\n#path = \"src/02.rs\"\n").unwrap();
+        std::fs::write(root.join("src/input/02.txt"), "puzzle").unwrap();
+
+        let steps = new_day(&root, 2).unwrap();
+        assert_eq!(steps.len(), 5);
+        assert_eq!(std::fs::read_to_string(root.join("src/02.rs")).unwrap(), "pub struct Day02;");
+        assert_eq!(std::fs::read_to_string(root.join("src/input/02.txt")).unwrap(), "puzzle");
+        assert_eq!(std::fs::read_to_string(root.join("src/problem/02.txt")).unwrap(), "");
+        assert!(std::fs::read_to_string(root.join("Cargo.toml")).unwrap().starts_with("[[bin]]"));
+
+        assert!(new_day(&root, 2).is_err());
+        assert!(new_day(&root, 26).is_err());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,266 @@
+use crate::{Board2D, Point32};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// What a search learned: the cost of reaching each state it settled, the state
+/// each was reached from, and the goal it stopped at, if any.
+///
+/// Searches stop at the first goal settled, so `dist` is complete only when no goal
+/// was found, or when `is_goal` never matches.
+#[derive(Debug, Clone)]
+pub struct SearchResult<S> {
+    pub dist: HashMap<S, u64>,
+    pub prev: HashMap<S, S>,
+    pub goal: Option<S>,
+}
+
+impl<S: Eq + Hash + Clone> SearchResult<S> {
+    fn new() -> Self {
+        Self { dist: HashMap::new(), prev: HashMap::new(), goal: None }
+    }
+
+    pub fn distance(&self, s: &S) -> Option<u64> {
+        self.dist.get(s).copied()
+    }
+
+    /// The cost of reaching the goal, when one was found.
+    pub fn goal_distance(&self) -> Option<u64> {
+        self.goal.as_ref().and_then(|g| self.distance(g))
+    }
+
+    /// The states from a start to `s` inclusive, or `None` when `s` was not reached.
+    pub fn path_to(&self, s: &S) -> Option<Vec<S>> {
+        if !self.dist.contains_key(s) {
+            return None;
+        }
+
+        let mut path = vec![s.clone()];
+        while let Some(prev) = self.prev.get(path.last().unwrap()) {
+            path.push(prev.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// The path to the goal, when one was found.
+    pub fn goal_path(&self) -> Option<Vec<S>> {
+        self.goal.as_ref().and_then(|g| self.path_to(g))
+    }
+}
+
+/// Breadth-first search where every step costs 1. `neighbours` lists the states one
+/// step from a state; the search stops at the first state for which `is_goal` holds.
+pub fn bfs<S, I, N, G>(starts: impl IntoIterator<Item = S>, mut neighbours: N, mut is_goal: G) -> SearchResult<S>
+where
+    S: Eq + Hash + Clone,
+    I: IntoIterator<Item = S>,
+    N: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    let mut result = SearchResult::new();
+    let mut queue = VecDeque::new();
+    for s in starts {
+        if !result.dist.contains_key(&s) {
+            result.dist.insert(s.clone(), 0);
+            queue.push_back(s);
+        }
+    }
+
+    while let Some(s) = queue.pop_front() {
+        if is_goal(&s) {
+            result.goal = Some(s);
+            break;
+        }
+
+        let d = result.dist[&s];
+        for n in neighbours(&s) {
+            if !result.dist.contains_key(&n) {
+                result.dist.insert(n.clone(), d + 1);
+                result.prev.insert(n.clone(), s.clone());
+                queue.push_back(n);
+            }
+        }
+    }
+
+    result
+}
+
+/// Lowest-cost search where `neighbours` lists `(state, step cost)` pairs.
+pub fn dijkstra<S, I, N, G>(starts: impl IntoIterator<Item = S>, neighbours: N, is_goal: G) -> SearchResult<S>
+where
+    S: Eq + Hash + Clone,
+    I: IntoIterator<Item = (S, u64)>,
+    N: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    astar(starts, neighbours, |_| 0, is_goal)
+}
+
+/// As `dijkstra`, guided by `heuristic`, an estimate of the remaining cost to a goal.
+/// The goal's cost is the lowest only if the heuristic never overestimates; a state
+/// reached more cheaply after it was expanded is expanded again.
+pub fn astar<S, I, N, H, G>(starts: impl IntoIterator<Item = S>, mut neighbours: N, mut heuristic: H, mut is_goal: G) -> SearchResult<S>
+where
+    S: Eq + Hash + Clone,
+    I: IntoIterator<Item = (S, u64)>,
+    N: FnMut(&S) -> I,
+    H: FnMut(&S) -> u64,
+    G: FnMut(&S) -> bool,
+{
+    let mut result = SearchResult::new();
+    let mut expanded = HashSet::new();
+    // The heap holds indexes into `states`, so states need not be `Ord`.
+    let mut states = vec![];
+    let mut heap = BinaryHeap::new();
+
+    for s in starts {
+        if !result.dist.contains_key(&s) {
+            result.dist.insert(s.clone(), 0);
+            heap.push(Reverse((heuristic(&s), 0, states.len())));
+            states.push(s);
+        }
+    }
+
+    while let Some(Reverse((_, d, i))) = heap.pop() {
+        let s = states[i].clone();
+        if result.dist[&s] < d {
+            continue;
+        }
+        if is_goal(&s) {
+            result.goal = Some(s);
+            break;
+        }
+
+        expanded.insert(s.clone());
+        for (n, cost) in neighbours(&s) {
+            let nd = d + cost;
+            if result.dist.get(&n).map_or(true, |x| nd < *x) {
+                result.dist.insert(n.clone(), nd);
+                result.prev.insert(n.clone(), s.clone());
+                heap.push(Reverse((nd + heuristic(&n), nd, states.len())));
+                states.push(n);
+            }
+        }
+    }
+
+    // Keep only expanded states and the goal, as the rest may still have been lowered.
+    result.dist.retain(|s, _| expanded.contains(s) || result.goal.as_ref() == Some(s));
+    result
+}
+
+impl<T> Board2D<T> {
+    /// The north, east, south and west neighbours of `p` whose cells are `open`.
+    pub fn open_neighbours4<'a, F>(&'a self, p: Point32, open: &'a F) -> impl Iterator<Item = Point32> + 'a
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.neighbours4(p).filter(move |(_, _, v)| open(v)).map(|(_, n, _)| n)
+    }
+
+    /// Step counts to every open cell reachable from `start` in four directions.
+    pub fn flood<F>(&self, start: Point32, open: F) -> SearchResult<Point32>
+    where
+        F: Fn(&T) -> bool,
+    {
+        bfs([start], |p| self.open_neighbours4(*p, &open).collect::<Vec<_>>(), |_| false)
+    }
+
+    /// A shortest four-direction path over open cells from `start` to `goal`, inclusive.
+    pub fn shortest_path<F>(&self, start: Point32, goal: Point32, open: F) -> Option<Vec<Point32>>
+    where
+        F: Fn(&T) -> bool,
+    {
+        astar(
+            [start],
+            |p| self.open_neighbours4(*p, &open).map(|n| (n, 1)).collect::<Vec<_>>(),
+            |p| p.manhattan(goal) as u64,
+            |p| *p == goal,
+        ).goal_path()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Compass;
+
+    fn pt(x: i32, y: i32) -> Point32 {
+        Point32 { x, y }
+    }
+
+    fn maze() -> Board2D<char> {
+        Board2D::try_from("S..#....\n.#.#.##.\n.#...#..\n.####.#.\n......#E").unwrap()
+    }
+
+    #[test]
+    fn board_paths() {
+        let b = maze();
+        let (start, end) = (b.find(&'S').unwrap(), b.find(&'E').unwrap());
+        let path = b.shortest_path(start, end, |c| *c != '#').unwrap();
+        assert_eq!(path.len(), 16);
+        assert_eq!((path[0], path[15]), (start, end));
+        assert!(path.windows(2).all(|w| w[0].manhattan(w[1]) == 1));
+        assert!(b.shortest_path(start, pt(3, 0), |c| *c != '#').is_none());
+
+        let flood = b.flood(start, |c| *c != '#');
+        assert_eq!(flood.distance(&end), Some(15));
+        assert_eq!(flood.distance(&pt(0, 4)), Some(4));
+        assert_eq!(flood.dist.len(), b.iter().filter(|c| **c != '#').count());
+        assert_eq!(flood.path_to(&pt(0, 2)), Some(vec![start, pt(0, 1), pt(0, 2)]));
+    }
+
+    #[test]
+    fn bfs_goal() {
+        let r = bfs([1u64], |x| [x * 2, x + 1], |x| *x == 10);
+        assert_eq!(r.goal_distance(), Some(4));
+        assert_eq!(r.goal_path(), Some(vec![1, 2, 4, 5, 10]));
+        assert_eq!(r.path_to(&1), Some(vec![1]));
+    }
+
+    #[test]
+    fn weighted_states() {
+        // Moving forward costs 1 and turning costs 1000, with the state a position and heading.
+        let b = maze();
+        let (start, end) = (b.find(&'S').unwrap(), b.find(&'E').unwrap());
+        let neighbours = |(p, dir): &(Point32, Compass)| {
+            let mut next = vec![((*p, dir.cardinal_left()), 1000), ((*p, dir.cardinal_right()), 1000)];
+            let ahead = p.step(*dir, 1);
+            if b.get(ahead).is_some_and(|c| *c != '#') {
+                next.push(((ahead, *dir), 1));
+            }
+            next
+        };
+
+        let d = dijkstra([(start, Compass::East)], neighbours, |(p, _)| *p == end);
+        let a = astar([(start, Compass::East)], neighbours, |(p, _)| p.manhattan(end) as u64, |(p, _)| *p == end);
+        assert_eq!(d.goal_distance(), Some(5015));
+        assert_eq!(a.goal_distance(), d.goal_distance());
+        assert_eq!(a.goal_path().unwrap().last().unwrap().0, end);
+        assert!(a.dist.len() <= d.dist.len());
+
+        let none = dijkstra([(start, Compass::East)], neighbours, |(p, _)| *p == pt(3, 0));
+        assert_eq!(none.goal, None);
+        assert_eq!(none.dist.len(), 4 * b.iter().filter(|c| **c != '#').count());
+    }
+
+    #[test]
+    fn inconsistent_heuristic() {
+        // Admissible but not consistent: 2 is expected to cost 4 more, so 3 is first
+        // expanded via 1 and must be reopened once 2 finds it more cheaply.
+        let edges = |x: &u32| match x {
+            0 => vec![(1, 1), (2, 2)],
+            1 => vec![(3, 3)],
+            2 => vec![(3, 1)],
+            3 => vec![(4, 3)],
+            _ => vec![],
+        };
+        let h = |x: &u32| if *x == 2 { 4 } else { 0 };
+
+        let a = astar([0], edges, h, |x| *x == 4);
+        assert_eq!(a.goal_distance(), Some(6));
+        assert_eq!(a.goal_path(), Some(vec![0, 2, 3, 4]));
+        assert_eq!(a.distance(&3), Some(3));
+        assert_eq!(dijkstra([0], edges, |x| *x == 4).goal_distance(), Some(6));
+    }
+}
@@ -0,0 +1,267 @@
+use crate::answer::Answer;
+use crate::answers::Answers;
+use crate::bench::{bench, format_bench, BenchOptions, BenchResult};
+use crate::input::InputSource;
+use crate::parallel::{solve_timed, DayResult};
+use crate::parse::ParseError;
+use crate::store::YEAR;
+use crate::submit::{submit_part_from_args, Submitter};
+use std::io;
+
+/// A day's puzzle: the input is parsed once into `Model`, which both parts borrow.
+/// Work needed by both parts (say part 1's result, reused by part 2) can be cached
+/// in the model with a `std::sync::OnceLock`, so it is computed once even when the
+/// parts run in parallel.
+pub trait Solution {
+    const DAY: u32;
+
+    /// Shared between threads when the parts are solved in parallel.
+    type Model: Sync;
+    type Answer1: Into<Answer>;
+    type Answer2: Into<Answer>;
+
+    fn default_input() -> &'static str;
+    fn parse(s: &str) -> Result<Self::Model, ParseError>;
+    fn part1(model: &Self::Model) -> Self::Answer1;
+    fn part2(model: &Self::Model) -> Self::Answer2;
+}
+
+/// Entry point for a `dayNN` binary, reading the input chosen by `--input`,
+/// `--input-dir` or `AOC_INPUT_DIR` before falling back to the embedded input.
+/// With `--bench` the parts are timed instead of printed, and with `--submit <part>`
+/// that part's answer is posted to adventofcode.com.
+pub fn run<S: Solution>() {
+    let args = BenchOptions::from_args(std::env::args().skip(1))
+        .and_then(|(options, args)| Ok((options, submit_part_from_args(args)?)))
+        .and_then(|(options, (submit, args))| Ok((options, submit, InputSource::from_args(args)?.0)));
+
+    let (options, submit, source) = match args {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+
+    let input = match source.load(S::DAY, S::default_input) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Unable to read input for day {:02}: {}", S::DAY, e);
+            std::process::exit(1);
+        }
+    };
+
+    let result = match options {
+        Some(options) => bench::<S>(&input, options.runs)
+            .map(|r| print!("{}", format_bench(&[r], options.format))),
+        None => solve::<S>(&input).map(|(part1, part2)| {
+            let answers = Answers::load_or_default();
+            let name = source.name();
+            println!("{} ({})", part1, answers.check(S::DAY, &name, 1, &part1));
+            println!("{} ({})", part2, answers.check(S::DAY, &name, 2, &part2));
+
+            if let Some(part) = submit {
+                let answer = if part == 1 { &part1 } else { &part2 };
+                if let Err(e) = submit_answer(S::DAY, part, answer, &source) {
+                    eprintln!("Not submitted: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}", e.diagnostic(&input));
+        std::process::exit(1);
+    }
+}
+
+/// Defines a day binary's `main`, which runs `$day`. The day files are also modules
+/// of `aoc`, which never calls it, so it is allowed to be unused.
+#[macro_export]
+macro_rules! day_main {
+    ($day:ty) => {
+        #[allow(dead_code)]
+        fn main() {
+            $crate::run::<$day>();
+        }
+    };
+}
+
+fn submit_answer(day: u32, part: u32, answer: &Answer, source: &InputSource) -> Result<(), String> {
+    if source.name() != "input" {
+        return Err(format!("answers for {} are not for your puzzle input", source.name()));
+    }
+    if !answer.is_solved() {
+        return Err(format!("part {} is unsolved", part));
+    }
+
+    let attempt = Submitter::from_env()?.submit(YEAR, day, part, &answer.to_string())?;
+    match attempt.wait {
+        0 => println!("Submitted part {}: {}", part, attempt.outcome),
+        wait => println!("Submitted part {}: {} (wait {}s before the next attempt)", part, attempt.outcome, wait),
+    }
+    Ok(())
+}
+
+pub fn solve<S: Solution>(s: &str) -> Result<(Answer, Answer), ParseError> {
+    let model = S::parse(s)?;
+    Ok((S::part1(&model).into(), S::part2(&model).into()))
+}
+
+/// Type-erased entry for a single day, so the runner can hold every day in one list.
+#[derive(Debug, Copy, Clone)]
+pub struct Day {
+    pub day: u32,
+    default_input: fn() -> &'static str,
+    solve: fn(&str) -> Result<(Answer, Answer), ParseError>,
+    bench: fn(&str, usize) -> Result<BenchResult, ParseError>,
+    solve_timed: fn(&str, bool) -> Result<DayResult, ParseError>,
+}
+
+impl Day {
+    pub fn of<S: Solution>() -> Self {
+        Day {
+            day: S::DAY,
+            default_input: S::default_input,
+            solve: solve::<S>,
+            bench: bench::<S>,
+            solve_timed: solve_timed::<S>,
+        }
+    }
+
+    pub fn default_input(&self) -> &'static str {
+        (self.default_input)()
+    }
+
+    pub fn load_input(&self, source: &InputSource) -> io::Result<String> {
+        source.load(self.day, self.default_input)
+    }
+
+    pub fn solve(&self, s: &str) -> Result<(Answer, Answer), ParseError> {
+        (self.solve)(s)
+    }
+
+    pub fn bench(&self, s: &str, runs: usize) -> Result<BenchResult, ParseError> {
+        (self.bench)(s, runs)
+    }
+
+    /// Solves once with timings, running the two parts in parallel if asked.
+    pub fn solve_timed(&self, s: &str, parallel: bool) -> Result<DayResult, ParseError> {
+        (self.solve_timed)(s, parallel)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Registry {
+    days: Vec<Day>,
+}
+
+impl Registry {
+    pub fn with<S: Solution>(mut self) -> Self {
+        self.days.push(Day::of::<S>());
+        self.days.sort_by_key(|d| d.day);
+        self
+    }
+
+    pub fn get(&self, day: u32) -> Option<&Day> {
+        self.days.iter().find(|d| d.day == day)
+    }
+
+    pub fn days(&self) -> &[Day] {
+        &self.days
+    }
+
+    pub fn select(&self, days: &DaySelection) -> Vec<&Day> {
+        self.days.iter().filter(|d| days.contains(d.day)).collect()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum DaySelection {
+    All,
+    One(u32),
+    Range(u32, u32),
+}
+
+impl DaySelection {
+    pub fn contains(&self, day: u32) -> bool {
+        match self {
+            DaySelection::All => true,
+            DaySelection::One(d) => *d == day,
+            DaySelection::Range(a, b) => (*a..=*b).contains(&day),
+        }
+    }
+}
+
+impl TryFrom<&str> for DaySelection {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let day = |x: &str| {
+            let x = x.trim().trim_start_matches("day");
+            x.parse::<u32>().map_err(|_| format!("Invalid day: {}", s))
+        };
+
+        if s == "all" {
+            return Ok(DaySelection::All);
+        }
+        if let Some((a, b)) = s.split_once("..").or_else(|| s.split_once('-')) {
+            let (a, b) = (day(a)?, day(b.trim_start_matches('='))?);
+            if a > b {
+                return Err(format!("Invalid day range: {}", s));
+            }
+            return Ok(DaySelection::Range(a, b));
+        }
+        Ok(DaySelection::One(day(s)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse_token;
+
+    struct Example;
+
+    impl Solution for Example {
+        const DAY: u32 = 3;
+
+        type Model = Vec<i64>;
+        type Answer1 = i64;
+        type Answer2 = usize;
+
+        fn default_input() -> &'static str { "1 2 3" }
+        fn parse(s: &str) -> Result<Self::Model, ParseError> {
+            s.split(' ').map(|x| parse_token(s, x)).collect()
+        }
+        fn part1(model: &Self::Model) -> i64 { model.iter().sum() }
+        fn part2(model: &Self::Model) -> usize { model.len() }
+    }
+
+    #[test]
+    fn solve_example() {
+        assert_eq!(solve::<Example>("4 5"), Ok((Answer::Int(9), Answer::Int(2))));
+        assert_eq!(solve::<Example>("4 x").unwrap_err().column, 3);
+
+        let reg = Registry::default().with::<Example>();
+        let day = reg.get(3).unwrap();
+        assert_eq!(day.solve(day.default_input()), Ok((Answer::Int(6), Answer::Int(3))));
+        assert!(reg.get(4).is_none());
+    }
+
+    #[test]
+    fn day_selection() {
+        assert_eq!(DaySelection::try_from("all"), Ok(DaySelection::All));
+        assert_eq!(DaySelection::try_from("6"), Ok(DaySelection::One(6)));
+        assert_eq!(DaySelection::try_from("day06"), Ok(DaySelection::One(6)));
+        assert_eq!(DaySelection::try_from("3..5"), Ok(DaySelection::Range(3, 5)));
+        assert_eq!(DaySelection::try_from("3..=5"), Ok(DaySelection::Range(3, 5)));
+        assert_eq!(DaySelection::try_from("3-5"), Ok(DaySelection::Range(3, 5)));
+        assert!(DaySelection::try_from("5..3").is_err());
+        assert!(DaySelection::try_from("x").is_err());
+
+        assert!(DaySelection::Range(3, 5).contains(5));
+        assert!(!DaySelection::Range(3, 5).contains(6));
+    }
+}
@@ -0,0 +1,232 @@
+use crate::http::{contact_from_env, HttpClient, AOC_URL};
+use crate::input_file_name;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub const YEAR: u32 = 2024;
+pub const SESSION_VAR: &str = "AOC_SESSION";
+pub const CACHE_DIR_VAR: &str = "AOC_INPUT_CACHE";
+pub const FETCH_VAR: &str = "AOC_FETCH";
+pub const CACHE_DIR: &str = ".inputs";
+
+/// Downloads the puzzle input for a day on a cache miss.
+pub trait Fetcher {
+    fn fetch(&self, year: u32, day: u32) -> Result<String, String>;
+}
+
+/// Fetches inputs from adventofcode.com using the session cookie from `AOC_SESSION`,
+/// identified by the contact in `AOC_CONTACT`.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct HttpFetcher {
+    pub client: HttpClient,
+}
+
+impl HttpFetcher {
+    pub fn from_env() -> Self {
+        let session = std::env::var(SESSION_VAR).ok().filter(|x| !x.is_empty());
+        HttpFetcher { client: HttpClient::new(AOC_URL, session, contact_from_env()) }
+    }
+}
+
+impl Fetcher for HttpFetcher {
+    fn fetch(&self, year: u32, day: u32) -> Result<String, String> {
+        if self.client.session.is_none() {
+            return Err(format!("Set {} to your adventofcode.com session cookie to download inputs", SESSION_VAR));
+        }
+
+        let response = self.client.get(&format!("/{}/day/{}/input", year, day))?;
+        match response.is_success() {
+            true => Ok(response.body),
+            false => Err(format!("Fetching {} day {} failed with status {}: {}",
+                year, day, response.status, response.body.lines().next().unwrap_or_default())),
+        }
+    }
+}
+
+/// Copies inputs from a local directory of `NN.txt` files.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct DirFetcher {
+    pub dir: PathBuf,
+}
+
+impl Fetcher for DirFetcher {
+    fn fetch(&self, _year: u32, day: u32) -> Result<String, String> {
+        let path = self.dir.join(input_file_name(day));
+        std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+/// Puzzle inputs cached on disk as `<root>/<year>/NN.txt`, fetched once on a miss.
+///
+/// adventofcode.com ends every input with a newline, so a fetched input that is empty
+/// or lacks one was cut short and is rejected rather than cached. Accepted inputs are
+/// written to a temporary file of their own and renamed into place, so the cache never
+/// holds a partial file, even with several writers; any cached file with content is
+/// therefore used as is, and only an empty one counts as a miss.
+///
+/// `HttpFetcher` downloads with `curl`, which must be on the `PATH`.
+pub struct InputStore {
+    root: PathBuf,
+    fetcher: Box<dyn Fetcher>,
+}
+
+impl InputStore {
+    pub fn new(root: &Path, fetcher: impl Fetcher + 'static) -> Self {
+        InputStore { root: root.to_owned(), fetcher: Box::new(fetcher) }
+    }
+
+    /// Caches in `cache_root()`, fetching with `HttpFetcher`.
+    pub fn from_env() -> Self {
+        InputStore::new(&cache_root(), HttpFetcher::from_env())
+    }
+
+    pub fn path(&self, year: u32, day: u32) -> PathBuf {
+        self.root.join(year.to_string()).join(input_file_name(day))
+    }
+
+    /// The cached input, if there is one with content.
+    pub fn cached(&self, year: u32, day: u32) -> Option<String> {
+        std::fs::read_to_string(self.path(year, day)).ok().filter(|s| !s.trim().is_empty())
+    }
+
+    /// The cached input, fetching and caching it first on a miss.
+    pub fn get(&self, year: u32, day: u32) -> Result<String, String> {
+        if let Some(s) = self.cached(year, day) {
+            return Ok(s);
+        }
+
+        let s = self.fetcher.fetch(year, day)?;
+        if !is_complete(&s) {
+            return Err(format!("Fetched input for {} day {} is empty or truncated", year, day));
+        }
+
+        // Unique per process and call, so concurrent fetches never share a partial file.
+        static WRITES: AtomicUsize = AtomicUsize::new(0);
+        let path = self.path(year, day);
+        let tmp = path.with_extension(format!("{}-{}.tmp", std::process::id(), WRITES.fetch_add(1, Ordering::Relaxed)));
+        let write = |p: &Path| std::fs::create_dir_all(self.root.join(year.to_string()))
+            .and_then(|_| std::fs::write(p, &s))
+            .and_then(|_| std::fs::rename(p, &path));
+        write(&tmp).map_err(|e| {
+            let _ = std::fs::remove_file(&tmp);
+            format!("{}: {}", path.display(), e)
+        })?;
+
+        Ok(s)
+    }
+}
+
+/// Whether missing inputs may be downloaded, which needs `AOC_FETCH` set to anything
+/// but empty or `0`. Without it only inputs already in the cache are used.
+pub fn fetch_enabled() -> bool {
+    std::env::var(FETCH_VAR).is_ok_and(|x| !x.is_empty() && x != "0")
+}
+
+/// `AOC_INPUT_CACHE` if set, otherwise `.inputs` in the package root (when run by cargo)
+/// or the current directory.
+pub fn cache_root() -> PathBuf {
+    match std::env::var_os(CACHE_DIR_VAR) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => std::env::var_os("CARGO_MANIFEST_DIR")
+            .map(|dir| PathBuf::from(dir).join(CACHE_DIR))
+            .unwrap_or_else(|| PathBuf::from(CACHE_DIR)),
+    }
+}
+
+fn is_complete(s: &str) -> bool {
+    !s.trim().is_empty() && s.ends_with('\n')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct CountingFetcher {
+        input: &'static str,
+        count: Rc<Cell<usize>>,
+    }
+
+    impl Fetcher for CountingFetcher {
+        fn fetch(&self, _year: u32, _day: u32) -> Result<String, String> {
+            self.count.set(self.count.get() + 1);
+            Ok(self.input.to_owned())
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("advent-store-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn fetches_once() {
+        let dir = temp_dir("once");
+        let count = Rc::new(Cell::new(0));
+        let store = InputStore::new(&dir, CountingFetcher { input: "1\n2\n", count: count.clone() });
+
+        assert_eq!(store.cached(2024, 3), None);
+        assert_eq!(store.get(2024, 3).unwrap(), "1\n2\n");
+        assert_eq!(store.get(2024, 3).unwrap(), "1\n2\n");
+        assert_eq!(count.get(), 1);
+        assert_eq!(std::fs::read_to_string(dir.join("2024/03.txt")).unwrap(), "1\n2\n");
+        assert_eq!(std::fs::read_dir(dir.join("2024")).unwrap().count(), 1);
+
+        // A cached input is used as is, even without a trailing newline.
+        std::fs::write(dir.join("2024/03.txt"), "1\n2").unwrap();
+        assert_eq!(store.get(2024, 3).unwrap(), "1\n2");
+        assert_eq!(count.get(), 1);
+
+        // An empty cache entry is fetched again.
+        std::fs::write(dir.join("2024/03.txt"), " \n").unwrap();
+        assert_eq!(store.get(2024, 3).unwrap(), "1\n2\n");
+        assert_eq!(count.get(), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_truncated() {
+        let dir = temp_dir("truncated");
+        let count = Rc::new(Cell::new(0));
+        let store = InputStore::new(&dir, CountingFetcher { input: "", count });
+        assert!(store.get(2024, 1).is_err());
+        assert!(!store.path(2024, 1).exists());
+    }
+
+    #[test]
+    fn from_dir() {
+        let dir = temp_dir("dir");
+        std::fs::create_dir_all(dir.join("mock")).unwrap();
+        std::fs::write(dir.join("mock/05.txt"), "abc\n").unwrap();
+
+        let store = InputStore::new(&dir.join("cache"), DirFetcher { dir: dir.join("mock") });
+        assert_eq!(store.get(2023, 5).unwrap(), "abc\n");
+        assert!(store.path(2023, 5).exists());
+        assert!(store.get(2023, 6).is_err());
+
+        // A body cut short before its final newline is rejected and nothing is cached.
+        std::fs::write(dir.join("mock/07.txt"), "1 2\n3").unwrap();
+        assert!(store.get(2023, 7).unwrap_err().contains("truncated"));
+        assert!(!store.path(2023, 7).exists());
+        std::fs::write(dir.join("mock/07.txt"), "1 2\n3 4\n").unwrap();
+        assert_eq!(store.get(2023, 7).unwrap(), "1 2\n3 4\n");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn from_server() {
+        let (url, server) = crate::http::tests::serve(vec![(200, "7 8\n".to_owned()), (400, "Please log in\n".to_owned())]);
+        let fetcher = HttpFetcher { client: HttpClient::new(&url, Some("s".to_owned()), Some("me".to_owned())) };
+
+        assert_eq!(fetcher.fetch(2024, 7).unwrap(), "7 8\n");
+        assert!(fetcher.fetch(2024, 8).unwrap_err().contains("Please log in"));
+        assert!(server.join().unwrap()[0].starts_with("GET /2024/day/7/input "));
+
+        let fetcher = HttpFetcher { client: HttpClient::new(&url, None, Some("me".to_owned())) };
+        assert!(fetcher.fetch(2024, 7).unwrap_err().contains(SESSION_VAR));
+    }
+}
@@ -0,0 +1,366 @@
+use crate::answer::Answer;
+use crate::http::{contact_from_env, HttpClient, AOC_URL};
+use crate::store::{cache_root, SESSION_VAR};
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const HISTORY_FILE: &str = "submissions.tsv";
+
+/// What adventofcode.com said about a submitted answer.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    /// Submitted too soon after the previous attempt.
+    Throttled,
+    /// The part is already solved, or not yet unlocked.
+    WrongLevel,
+    Unknown,
+}
+
+impl Outcome {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Correct => "correct",
+            Outcome::TooHigh => "too-high",
+            Outcome::TooLow => "too-low",
+            Outcome::Wrong => "wrong",
+            Outcome::Throttled => "throttled",
+            Outcome::WrongLevel => "wrong-level",
+            Outcome::Unknown => "unknown",
+        }
+    }
+
+    /// Whether the answer itself was judged, as opposed to the attempt being rejected.
+    pub fn is_verdict(&self) -> bool {
+        matches!(self, Outcome::Correct | Outcome::TooHigh | Outcome::TooLow | Outcome::Wrong)
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl TryFrom<&str> for Outcome {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        [Outcome::Correct, Outcome::TooHigh, Outcome::TooLow, Outcome::Wrong, Outcome::Throttled, Outcome::WrongLevel, Outcome::Unknown]
+            .into_iter()
+            .find(|x| x.as_str() == s)
+            .ok_or_else(|| format!("Unknown outcome: {}", s))
+    }
+}
+
+/// A submitted answer and its outcome, with the time it was made and how many
+/// seconds the site asked us to wait before the next one.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Attempt {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub answer: String,
+    pub outcome: Outcome,
+    pub at: u64,
+    pub wait: u64,
+}
+
+impl Attempt {
+    /// Reads the outcome and any requested wait from the response page.
+    pub fn from_response(year: u32, day: u32, part: u32, answer: &str, at: u64, body: &str) -> Self {
+        let outcome = if body.contains("That's the right answer") {
+            Outcome::Correct
+        } else if body.contains("your answer is too high") {
+            Outcome::TooHigh
+        } else if body.contains("your answer is too low") {
+            Outcome::TooLow
+        } else if body.contains("That's not the right answer") {
+            Outcome::Wrong
+        } else if body.contains("You gave an answer too recently") {
+            Outcome::Throttled
+        } else if body.contains("You don't seem to be solving the right level") {
+            Outcome::WrongLevel
+        } else {
+            Outcome::Unknown
+        };
+
+        Attempt { year, day, part, answer: answer.to_owned(), outcome, at, wait: parse_wait(body).unwrap_or(0) }
+    }
+
+    fn to_line(&self) -> String {
+        format!("{}\t{}\t{}\t{}\t{}\t{}\t{}\n", self.year, self.day, self.part, self.at, self.wait, self.outcome, self.answer)
+    }
+}
+
+impl TryFrom<&str> for Attempt {
+    type Error = String;
+
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        let fields = line.splitn(7, '\t').collect::<Vec<_>>();
+        let [year, day, part, at, wait, outcome, answer] = fields.as_slice() else {
+            return Err(format!("Expected 7 tab-separated fields: {}", line));
+        };
+        let int = |x: &str| x.parse::<u64>().map_err(|_| format!("Expected a number, found '{}': {}", x, line));
+
+        Ok(Attempt {
+            year: int(year)? as u32,
+            day: int(day)? as u32,
+            part: int(part)? as u32,
+            answer: answer.to_string(),
+            outcome: Outcome::try_from(*outcome)?,
+            at: int(at)?,
+            wait: int(wait)?,
+        })
+    }
+}
+
+/// Parses "You have 1m 5s left to wait" and "Please wait one minute" / "wait 5 minutes".
+fn parse_wait(body: &str) -> Option<u64> {
+    if let Some((_, rest)) = body.split_once("You have ") {
+        let (left, _) = rest.split_once(" left to wait")?;
+        return left.split_whitespace()
+            .map(|x| {
+                let (n, unit) = x.split_at(x.find(|c: char| !c.is_ascii_digit())?);
+                let n = n.parse::<u64>().ok()?;
+                match unit {
+                    "h" => Some(n * 3600),
+                    "m" => Some(n * 60),
+                    "s" => Some(n),
+                    _ => None,
+                }
+            })
+            .sum();
+    }
+
+    let (_, rest) = body.split_once("wait ")?;
+    let mut words = rest.split_whitespace();
+    let n = match words.next()? {
+        "one" => 1,
+        n => n.parse::<u64>().ok()?,
+    };
+    words.next().filter(|x| x.starts_with("minute")).map(|_| n * 60)
+}
+
+/// Every submission made, appended to a tab-separated file so that it survives between runs.
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct SubmitHistory {
+    path: Option<PathBuf>,
+    pub attempts: Vec<Attempt>,
+}
+
+impl SubmitHistory {
+    /// Loads the history at `path`; a missing file gives an empty history.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let attempts = match path.exists() {
+            true => std::fs::read_to_string(path)
+                .map_err(|e| format!("{}: {}", path.display(), e))?
+                .lines()
+                .filter(|x| !x.trim().is_empty())
+                .map(Attempt::try_from)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("{}: {}", path.display(), e))?,
+            false => vec![],
+        };
+        Ok(SubmitHistory { path: Some(path.to_owned()), attempts })
+    }
+
+    /// Adds `attempt`, appending it to the history file if there is one.
+    pub fn record(&mut self, attempt: Attempt) -> Result<(), String> {
+        if let Some(path) = &self.path {
+            let err = |e: std::io::Error| format!("{}: {}", path.display(), e);
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).map_err(err)?;
+            }
+            std::fs::OpenOptions::new().create(true).append(true).open(path)
+                .and_then(|mut f| f.write_all(attempt.to_line().as_bytes()))
+                .map_err(err)?;
+        }
+        self.attempts.push(attempt);
+        Ok(())
+    }
+
+    fn for_part(&self, year: u32, day: u32, part: u32) -> impl Iterator<Item = &Attempt> + '_ {
+        self.attempts.iter().filter(move |x| x.year == year && x.day == day && x.part == part)
+    }
+
+    /// Refuses answers that are already known to be wrong, outside the bounds set by
+    /// earlier "too high" and "too low" responses, or submitted while still throttled.
+    pub fn check(&self, year: u32, day: u32, part: u32, answer: &str, now: u64) -> Result<(), String> {
+        if answer.trim().is_empty() {
+            return Err("Refusing to submit an empty answer".to_string());
+        }
+        if let Some(x) = self.for_part(year, day, part).find(|x| x.outcome == Outcome::Correct) {
+            return Err(format!("Part {} is already solved with {}", part, x.answer));
+        }
+
+        let until = self.attempts.iter()
+            .filter(|x| x.year == year && x.day == day)
+            .map(|x| x.at + x.wait)
+            .max()
+            .unwrap_or(0);
+        if until > now {
+            return Err(format!("Wait {}s before submitting again", until - now));
+        }
+
+        if let Some(x) = self.for_part(year, day, part).find(|x| x.answer == answer && x.outcome.is_verdict()) {
+            return Err(format!("{} was already submitted and was {}", answer, x.outcome));
+        }
+
+        let answer = Answer::parse(answer);
+        for x in self.for_part(year, day, part) {
+            let previous = Answer::parse(&x.answer);
+            match x.outcome {
+                Outcome::TooLow if answer <= previous => return Err(format!("{} is not above {}, which was too low", answer, previous)),
+                Outcome::TooHigh if answer >= previous => return Err(format!("{} is not below {}, which was too high", answer, previous)),
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Posts answers to adventofcode.com, checking and recording each attempt in the history.
+pub struct Submitter {
+    pub client: HttpClient,
+    pub history: SubmitHistory,
+}
+
+impl Submitter {
+    /// Uses `AOC_SESSION`, `AOC_CONTACT` and the history file alongside the input cache.
+    pub fn from_env() -> Result<Self, String> {
+        let session = std::env::var(SESSION_VAR).ok().filter(|x| !x.is_empty())
+            .ok_or_else(|| format!("Set {} to your adventofcode.com session cookie to submit answers", SESSION_VAR))?;
+        let history = SubmitHistory::load(&cache_root().join(HISTORY_FILE))?;
+        Ok(Submitter { client: HttpClient::new(AOC_URL, Some(session), contact_from_env()), history })
+    }
+
+    pub fn submit(&mut self, year: u32, day: u32, part: u32, answer: &str) -> Result<Attempt, String> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|x| x.as_secs()).unwrap_or(0);
+        self.history.check(year, day, part, answer, now)?;
+
+        let response = self.client.post_form(&format!("/{}/day/{}/answer", year, day),
+            &[("level", &part.to_string()), ("answer", answer)])?;
+        if !response.is_success() {
+            return Err(format!("Submitting failed with status {}", response.status));
+        }
+
+        let attempt = Attempt::from_response(year, day, part, answer, now, &response.body);
+        self.history.record(attempt.clone())?;
+        Ok(attempt)
+    }
+}
+
+/// Parses `--submit <1|2>` out of `args`, returning the part to submit and the remaining arguments.
+pub fn submit_part_from_args<I>(args: I) -> Result<(Option<u32>, Vec<String>), String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut part = None;
+    let mut rest = vec![];
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--submit" => {
+                let value = args.next().ok_or("Missing value for --submit")?;
+                part = Some(match value.as_str() {
+                    "1" => 1,
+                    "2" => 2,
+                    _ => return Err(format!("Expected part 1 or 2, found {}", value)),
+                });
+            },
+            _ => rest.push(arg),
+        }
+    }
+
+    Ok((part, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(part: u32, answer: &str, outcome: Outcome, at: u64, wait: u64) -> Attempt {
+        Attempt { year: 2024, day: 6, part, answer: answer.to_owned(), outcome, at, wait }
+    }
+
+    #[test]
+    fn responses() {
+        let a = Attempt::from_response(2024, 6, 1, "5", 100, "<p>That's the right answer! You are one gold star closer.</p>");
+        assert_eq!((a.outcome, a.wait), (Outcome::Correct, 0));
+
+        let a = Attempt::from_response(2024, 6, 1, "5", 100, "<p>That's not the right answer; your answer is too high. Please wait one minute before trying again.</p>");
+        assert_eq!((a.outcome, a.wait), (Outcome::TooHigh, 60));
+
+        let a = Attempt::from_response(2024, 6, 1, "5", 100, "That's not the right answer. please wait 5 minutes before trying again.");
+        assert_eq!((a.outcome, a.wait), (Outcome::Wrong, 300));
+
+        let a = Attempt::from_response(2024, 6, 1, "5", 100, "You gave an answer too recently. You have 1m 5s left to wait.");
+        assert_eq!((a.outcome, a.wait), (Outcome::Throttled, 65));
+
+        let a = Attempt::from_response(2024, 6, 1, "5", 100, "You don't seem to be solving the right level.");
+        assert_eq!(a.outcome, Outcome::WrongLevel);
+        assert_eq!(Attempt::try_from(a.to_line().trim_end()), Ok(a));
+    }
+
+    #[test]
+    fn refuses_known_answers() {
+        let history = SubmitHistory {
+            path: None,
+            attempts: vec![
+                attempt(1, "100", Outcome::TooLow, 0, 60),
+                attempt(1, "500", Outcome::TooHigh, 100, 60),
+                attempt(1, "abc", Outcome::Wrong, 200, 60),
+                attempt(2, "7", Outcome::Correct, 300, 0),
+            ],
+        };
+
+        assert!(history.check(2024, 6, 1, "200", 1000).is_ok());
+        assert!(history.check(2024, 6, 1, "100", 1000).unwrap_err().contains("already submitted"));
+        assert!(history.check(2024, 6, 1, "99", 1000).unwrap_err().contains("too low"));
+        assert!(history.check(2024, 6, 1, "600", 1000).unwrap_err().contains("too high"));
+        assert!(history.check(2024, 6, 1, "abc", 1000).unwrap_err().contains("already submitted"));
+        assert!(history.check(2024, 6, 1, "", 1000).is_err());
+        assert!(history.check(2024, 6, 2, "8", 1000).unwrap_err().contains("already solved"));
+        assert!(history.check(2024, 6, 1, "200", 299).unwrap_err().contains("Wait 1s"));
+        assert!(history.check(2024, 7, 1, "100", 0).is_ok());
+    }
+
+    #[test]
+    fn submit_to_server() {
+        let dir = std::env::temp_dir().join(format!("advent-submit-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join(HISTORY_FILE);
+
+        let (url, server) = crate::http::tests::serve(vec![(200, "<p>That's not the right answer; your answer is too low.</p>".to_owned())]);
+        let mut submitter = Submitter {
+            client: HttpClient::new(&url, Some("s".to_owned()), Some("me".to_owned())),
+            history: SubmitHistory::load(&path).unwrap(),
+        };
+
+        let attempt = submitter.submit(2024, 6, 2, "1748").unwrap();
+        assert_eq!(attempt.outcome, Outcome::TooLow);
+        assert!(submitter.submit(2024, 6, 2, "1000").unwrap_err().contains("too low"));
+        assert!(server.join().unwrap()[0].ends_with("level=2&answer=1748"));
+
+        let history = SubmitHistory::load(&path).unwrap();
+        assert_eq!(history.attempts, vec![attempt]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn part_from_args() {
+        let args = |s: &str| s.split_whitespace().map(|x| x.to_owned()).collect::<Vec<_>>();
+        assert_eq!(submit_part_from_args(args("--submit 2 -i a.txt")).unwrap(), (Some(2), args("-i a.txt")));
+        assert_eq!(submit_part_from_args(args("6")).unwrap(), (None, args("6")));
+        assert!(submit_part_from_args(args("--submit 3")).is_err());
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{Board2D, Compass, Point32};
+
+/// A set of `(Point32, Compass)` states on a `width` by `height` grid, one bit per
+/// state, so membership never hashes and never collides however large the grid.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct VisitedSet {
+    /// One byte per cell, with bit `Compass::index()` set for each direction seen.
+    cells: Vec<u8>,
+    width: usize,
+    height: usize,
+    len: usize,
+}
+
+impl VisitedSet {
+    /// An empty set, failing when a cell of the grid cannot be addressed by `Point32`.
+    pub fn new(width: usize, height: usize) -> Result<Self, String> {
+        let fits = |n: usize| n <= 1 << 31;
+        match width.checked_mul(height) {
+            Some(size) if fits(width) && fits(height) => Ok(Self { cells: vec![0; size], width, height, len: 0 }),
+            _ => Err(format!("A {}x{} grid is too large", width, height)),
+        }
+    }
+
+    /// An empty set covering `board`.
+    pub fn for_board<T>(board: &Board2D<T>) -> Self {
+        Self::new(board.width(), board.height()).expect("a board's size is addressable")
+    }
+
+    fn offset_of(&self, p: Point32) -> Option<usize> {
+        let in_bounds = p.x >= 0 && p.y >= 0 && (p.x as usize) < self.width && (p.y as usize) < self.height;
+        in_bounds.then(|| (p.y as usize) * self.width + (p.x as usize))
+    }
+
+    /// Adds a state, returning whether it was new. Panics when `p` is outside the grid.
+    pub fn insert(&mut self, p: Point32, dir: Compass) -> bool {
+        let i = self.offset_of(p).unwrap_or_else(|| panic!("{:?} is outside the grid", p));
+        let bit = 1 << dir.index();
+        let new = self.cells[i] & bit == 0;
+        self.cells[i] |= bit;
+        self.len += new as usize;
+        new
+    }
+
+    pub fn contains(&self, p: Point32, dir: Compass) -> bool {
+        self.offset_of(p).is_some_and(|i| self.cells[i] & (1 << dir.index()) != 0)
+    }
+
+    /// Whether `p` was visited facing any direction.
+    pub fn contains_point(&self, p: Point32) -> bool {
+        self.offset_of(p).is_some_and(|i| self.cells[i] != 0)
+    }
+
+    /// The number of states in the set.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Every point visited facing any direction, in row-major order.
+    pub fn points(&self) -> impl Iterator<Item = Point32> + '_ {
+        self.cells.iter()
+            .enumerate()
+            .filter(|(_, x)| **x != 0)
+            .map(|(i, _)| Point32 { x: (i % self.width) as i32, y: (i / self.width) as i32 })
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.fill(0);
+        self.len = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pt(x: i32, y: i32) -> Point32 {
+        Point32 { x, y }
+    }
+
+    #[test]
+    fn insert_and_contains() {
+        let mut set = VisitedSet::new(3, 2).unwrap();
+        assert!(set.is_empty());
+        assert!(set.insert(pt(2, 1), Compass::West));
+        assert!(!set.insert(pt(2, 1), Compass::West));
+        assert!(set.insert(pt(2, 1), Compass::North));
+        assert!(set.insert(pt(0, 0), Compass::NorthWest));
+        assert_eq!(set.len(), 3);
+
+        assert!(set.contains(pt(2, 1), Compass::North));
+        assert!(!set.contains(pt(2, 1), Compass::South));
+        assert!(!set.contains(pt(-1, 0), Compass::North));
+        assert!(set.contains_point(pt(0, 0)));
+        assert!(!set.contains_point(pt(1, 0)));
+        assert!(!set.contains_point(pt(3, 0)));
+        assert_eq!(set.points().collect::<Vec<_>>(), vec![pt(0, 0), pt(2, 1)]);
+
+        set.clear();
+        assert!(set.is_empty() && set.points().next().is_none());
+    }
+
+    #[test]
+    fn no_collisions() {
+        // Wider than the 1024 columns a packed i32 key could hold.
+        let mut set = VisitedSet::new(2048, 2).unwrap();
+        set.insert(pt(1024, 0), Compass::East);
+        assert!(!set.contains(pt(0, 0), Compass::East));
+        assert!(!set.contains(pt(0, 1), Compass::East));
+        assert!(set.contains(pt(1024, 0), Compass::East));
+    }
+
+    #[test]
+    fn bounds() {
+        assert!(VisitedSet::new(usize::MAX, 2).is_err());
+        assert!(VisitedSet::new(1 << 32, 0).is_err());
+        assert!(VisitedSet::new(0, 0).unwrap().is_empty());
+        let board = Board2D::filled('.', 4, 5);
+        let set = VisitedSet::for_board(&board);
+        assert!(board.points().all(|p| !set.contains_point(p)));
+    }
+}
@@ -0,0 +1,148 @@
+mod watch;
+
+use advent::*;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+fn usage() -> &'static str {
+    "Usage: cargo xtask <command> [args]
+
+Commands:
+  work <day>                 Re-run, test and lint a day whenever its files change
+  lint [day]                 Clippy for one day, or the whole workspace
+  test <day> [--nocapture]   Run a day's tests
+  test-all                   Run every test in the workspace
+  run [days] [aoc args]      Solve days in release mode, e.g. `run 3..6 --parallel`
+  bench [days] [aoc args]    Time days in release mode, e.g. `bench 6 --runs 20`
+  new-day <day>              Create a day from src/template.rs"
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+enum Task {
+    Work(u32),
+    Lint(Option<u32>),
+    Test(u32, bool),
+    TestAll,
+    Run(Vec<String>),
+    Bench(Vec<String>),
+    NewDay(u32),
+}
+
+impl TryFrom<&[String]> for Task {
+    type Error = String;
+
+    fn try_from(args: &[String]) -> Result<Self, Self::Error> {
+        let (command, rest) = args.split_first().ok_or("Missing command")?;
+        let day = |i: usize| match rest.get(i).map(|x| DaySelection::try_from(x.as_str())) {
+            Some(Ok(DaySelection::One(day))) => Ok(day),
+            Some(Ok(_)) => Err(format!("{} takes a single day", command)),
+            Some(Err(e)) => Err(e),
+            None => Err(format!("{} needs a day", command)),
+        };
+
+        match command.as_str() {
+            "work" | "watch" => Ok(Task::Work(day(0)?)),
+            "lint" if rest.is_empty() => Ok(Task::Lint(None)),
+            "lint" => Ok(Task::Lint(Some(day(0)?))),
+            "test" => match rest.get(1).map(|x| x.as_str()) {
+                None => Ok(Task::Test(day(0)?, false)),
+                Some("--nocapture" | "--no-capture") => Ok(Task::Test(day(0)?, true)),
+                Some(x) => Err(format!("Unexpected argument: {}", x)),
+            },
+            "test-all" => Ok(Task::TestAll),
+            "run" => Ok(Task::Run(rest.to_vec())),
+            "bench" => Ok(Task::Bench(rest.to_vec())),
+            "new-day" => Ok(Task::NewDay(day(0)?)),
+            _ => Err(format!("Unknown command: {}", command)),
+        }
+    }
+}
+
+impl Task {
+    /// The cargo invocations for this task, run in order until one fails.
+    /// `Work` has none, as it runs its own loop in `watch`.
+    fn cargo_args(&self) -> Vec<Vec<String>> {
+        let bin = |day: &u32| format!("day{:02}", day);
+        let args = |s: &[&str]| s.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+
+        match self {
+            Task::Work(_) => vec![],
+            // The test profile lints the day with its tests; `--all-targets` would add every other target.
+            Task::Lint(Some(day)) => vec![args(&["clippy", "--bin", &bin(day), "--profile", "test", "--", "-D", "warnings"])],
+            Task::Lint(None) => vec![args(&["clippy", "--workspace", "--all-targets", "--", "-D", "warnings"])],
+            Task::Test(day, false) => vec![args(&["test", "--bin", &bin(day)])],
+            Task::Test(day, true) => vec![args(&["test", "--bin", &bin(day), "--", "--nocapture"])],
+            Task::TestAll => vec![args(&["test", "--workspace", "--no-fail-fast"])],
+            Task::Run(rest) => vec![[args(&["run", "--release", "--bin", "aoc", "--"]), rest.clone()].concat()],
+            Task::Bench(rest) => vec![[args(&["run", "--release", "--bin", "aoc", "--"]), rest.clone(), args(&["--bench"])].concat()],
+            Task::NewDay(day) => vec![args(&["run", "--bin", "new-day", "--", &day.to_string()])],
+        }
+    }
+}
+
+/// The workspace root, two levels above this crate.
+fn root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).ancestors().nth(2).expect("xtask lives in crates/xtask").to_owned()
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let task = match Task::try_from(args.as_slice()) {
+        Ok(task) => task,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, usage());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    if let Task::Work(day) = task {
+        watch::watch(&root(), day, &cargo);
+    }
+
+    for args in task.cargo_args() {
+        let status = Command::new(&cargo).args(&args).current_dir(root()).status();
+        match status {
+            Ok(status) if status.success() => (),
+            Ok(status) => return ExitCode::from(status.code().unwrap_or(1).clamp(1, 255) as u8),
+            Err(e) => {
+                eprintln!("Unable to run cargo {}: {}", args.join(" "), e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(s: &str) -> Result<Task, String> {
+        let args = s.split_whitespace().map(|x| x.to_owned()).collect::<Vec<_>>();
+        Task::try_from(args.as_slice())
+    }
+
+    #[test]
+    fn parse_tasks() {
+        assert_eq!(task("work day01"), Ok(Task::Work(1)));
+        assert_eq!(task("lint"), Ok(Task::Lint(None)));
+        assert_eq!(task("lint 6"), Ok(Task::Lint(Some(6))));
+        assert_eq!(task("test day06 --nocapture"), Ok(Task::Test(6, true)));
+        assert_eq!(task("run 3..6 --parallel"), Ok(Task::Run(vec!["3..6".to_owned(), "--parallel".to_owned()])));
+        assert!(task("").is_err());
+        assert!(task("test").is_err());
+        assert!(task("test all").is_err());
+        assert!(task("deploy").is_err());
+    }
+
+    #[test]
+    fn cargo_args() {
+        assert_eq!(task("test 8").unwrap().cargo_args(), vec![vec!["test", "--bin", "day08"]]);
+        assert_eq!(task("lint 6").unwrap().cargo_args(), vec![vec!["clippy", "--bin", "day06", "--profile", "test", "--", "-D", "warnings"]]);
+        assert_eq!(task("bench 6 --runs 3").unwrap().cargo_args()[0].join(" "), "run --release --bin aoc -- 6 --runs 3 --bench");
+        assert_eq!(task("new-day 9").unwrap().cargo_args()[0].join(" "), "run --bin new-day -- 9");
+        assert!(root().join("Cargo.toml").exists());
+    }
+}
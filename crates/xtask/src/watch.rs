@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+const POLL: Duration = Duration::from_millis(500);
+
+/// The files that affect a day: its source, its inputs and sidecar examples
+/// (`src/input/NN*.txt`), its problem text and the answers file.
+pub fn watched_files(root: &Path, day: u32) -> Vec<PathBuf> {
+    let mut files = vec![
+        root.join(format!("src/{:02}.rs", day)),
+        root.join(format!("src/problem/{:02}.txt", day)),
+        root.join("answers.toml"),
+    ];
+
+    let prefix = format!("{:02}", day);
+    if let Ok(entries) = std::fs::read_dir(root.join("src/input")) {
+        let mut inputs = entries
+            .filter_map(|x| x.ok())
+            .map(|x| x.path())
+            .filter(|x| {
+                let name = x.file_name().map(|x| x.to_string_lossy().into_owned()).unwrap_or_default();
+                name.starts_with(&prefix) && name.ends_with(".txt")
+            })
+            .collect::<Vec<_>>();
+        inputs.sort();
+        files.extend(inputs);
+    }
+
+    files
+}
+
+/// Modification time and length of each watched file; `None` when it is missing.
+fn snapshot(root: &Path, day: u32) -> BTreeMap<PathBuf, Option<(SystemTime, u64)>> {
+    watched_files(root, day)
+        .into_iter()
+        .map(|path| {
+            let meta = std::fs::metadata(&path).ok().and_then(|m| Some((m.modified().ok()?, m.len())));
+            (path, meta)
+        })
+        .collect()
+}
+
+/// Describes each answer line against the previous run's.
+pub fn diff_answers(previous: &[String], current: &[String]) -> Vec<String> {
+    current.iter()
+        .enumerate()
+        .map(|(i, line)| match previous.get(i) {
+            Some(old) if old == line => format!("Part {}: {} (unchanged)", i + 1, line),
+            Some(old) => format!("Part {}: {} (was {})", i + 1, line, old),
+            None => format!("Part {}: {}", i + 1, line),
+        })
+        .collect()
+}
+
+/// Rebuilds and re-runs one day, then its tests and lints, each time its files change.
+/// Runs until interrupted.
+pub fn watch(root: &Path, day: u32, cargo: &OsString) -> ! {
+    let bin = format!("day{:02}", day);
+    let cargo = |args: &[&str]| Command::new(cargo).args(args).current_dir(root).output();
+    let mut previous: Vec<String> = vec![];
+    let mut last = None;
+
+    loop {
+        let current = snapshot(root, day);
+        if last.as_ref() == Some(&current) {
+            std::thread::sleep(POLL);
+            continue;
+        }
+        last = Some(current);
+
+        print!("\x1b[2J\x1b[H");
+        println!("Watching {} (Ctrl-C to stop)\n", bin);
+
+        match cargo(&["run", "--quiet", "--bin", &bin]) {
+            Ok(output) if output.status.success() => {
+                let answers = String::from_utf8_lossy(&output.stdout).lines().map(|x| x.to_owned()).collect::<Vec<_>>();
+                for line in diff_answers(&previous, &answers) {
+                    println!("{}", line);
+                }
+                previous = answers;
+            },
+            Ok(output) => eprint!("{}", String::from_utf8_lossy(&output.stderr)),
+            Err(e) => eprintln!("Unable to run cargo: {}", e),
+        }
+
+        for (name, args) in [("Tests", ["test", "--quiet", "--bin", &bin]), ("Clippy", ["clippy", "--quiet", "--bin", &bin])] {
+            match cargo(&args) {
+                Ok(output) if output.status.success() => println!("\n{}: ok", name),
+                Ok(output) => {
+                    println!("\n{}: failed", name);
+                    print!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+                },
+                Err(e) => eprintln!("Unable to run cargo: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff() {
+        let lines = |s: &[&str]| s.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        let diff = diff_answers(&lines(&["1 (pass)", "2 (unknown)"]), &lines(&["1 (pass)", "3 (unknown)"]));
+        assert_eq!(diff, vec!["Part 1: 1 (pass) (unchanged)", "Part 2: 3 (unknown) (was 2 (unknown))"]);
+        assert_eq!(diff_answers(&[], &lines(&["5 (pass)"])), vec!["Part 1: 5 (pass)"]);
+    }
+
+    #[test]
+    fn files_for_day() {
+        let root = super::super::root();
+        let files = watched_files(&root, 8);
+        assert!(files.contains(&root.join("src/08.rs")));
+        assert!(files.contains(&root.join("src/input/08.txt")));
+        assert!(files.contains(&root.join("src/input/08test1.txt")));
+        assert!(!files.contains(&root.join("src/input/07.txt")));
+        assert!(snapshot(&root, 8).values().all(|x| x.is_some()));
+    }
+}
@@ -2,7 +2,9 @@
 use advent::*;
 use itertools::Itertools;
 
-struct InputModel {
+pub struct Day01;
+
+pub struct InputModel {
     lines: Vec<(i64, i64)>,
 }
 
@@ -15,65 +17,71 @@ impl InputModel {
     }
 }
 
-impl From<&str> for InputModel {
+impl TryFrom<&str> for InputModel {
+    type Error = ParseError;
 
-    fn from(s: &str) -> Self {
-        let lines = input_as_lines(s).iter()
-            .map(|a| a.split("   ").map(|b| b.parse::<i64>().unwrap()).collect::<Vec<_>>())
-            .map(|b| (b[0], b[1]))
-            .collect::<Vec<_>>();
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let lines = parse_lines(s, |a| {
+            match a.split("   ").collect::<Vec<_>>()[..] {
+                [left, right] => Ok((parse_token(a, left)?, parse_token(a, right)?)),
+                _ => Err(ParseError::new("Expected two location IDs", a).with_column(1)),
+            }
+        })?;
 
-        InputModel { lines }
+        Ok(InputModel { lines })
     }
 }
 
-fn default_input() -> &'static str {
-    include_input!(01)
-}
-
-pub fn part1() -> String {
-    let model = InputModel::from(default_input());
+impl Solution for Day01 {
+    const DAY: u32 = 1;
 
-    let left = model.left().iter().sorted().cloned().collect::<Vec<_>>();
-    let right = model.right().iter().sorted().cloned().collect::<Vec<_>>();
+    type Model = InputModel;
+    type Answer1 = i64;
+    type Answer2 = i64;
 
-    let sum = left.iter()
-        .zip(right.iter())
-        .map(|(a, b)| (a - b).abs())
-        .sum::<i64>();
+    fn default_input() -> &'static str {
+        include_input!(01)
+    }
 
-    sum.to_string()
-}
+    fn parse(s: &str) -> Result<InputModel, ParseError> {
+        InputModel::try_from(s)
+    }
 
-pub fn part2() -> String {
-    let model = InputModel::from(default_input());
+    fn part1(model: &InputModel) -> i64 {
+        let left = model.left().iter().sorted().cloned().collect::<Vec<_>>();
+        let right = model.right().iter().sorted().cloned().collect::<Vec<_>>();
 
-    let left = model.left();
-    let right = model.right();
+        left.iter()
+            .zip(right.iter())
+            .map(|(a, b)| (a - b).abs())
+            .sum::<i64>()
+    }
 
-    let sum = left.iter()
-        .map(|a| { *a * right.iter().filter(|b| **b == *a).count() as i64 })
-        .sum::<i64>();
+    fn part2(model: &InputModel) -> i64 {
+        let left = model.left();
+        let right = model.right();
 
-    sum.to_string()
+        left.iter()
+            .map(|a| { *a * right.iter().filter(|b| **b == *a).count() as i64 })
+            .sum::<i64>()
+    }
 }
 
-fn main() {
-    println!("{}", part1());
-    println!("{}", part2());
-}
+day_main!(Day01);
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn solve_part1() {
-        assert_eq!(part1(), "2176849");
-    }
+    fn parse_error() {
+        let err = InputModel::try_from("1   2\n3   x4\n5   6").err().unwrap();
+        assert_eq!((err.line, err.column), (2, 5));
+        assert_eq!(err.text, "x4");
 
-    #[test]
-    fn solve_part2() {
-        assert_eq!(part2(), "23384288");
+        let err = InputModel::try_from("1   2\n3").err().unwrap();
+        assert_eq!((err.line, err.column), (2, 1));
     }
+
+    answer_tests!(Day01);
 }
@@ -3,7 +3,9 @@ use advent::*;
 use itertools::Itertools;
 use std::cmp::Ordering;
 
-struct InputModel {
+pub struct Day02;
+
+pub struct InputModel {
     reports: Vec<Report>,
 }
 
@@ -11,21 +13,23 @@ struct Report {
     levels: Vec<i64>,
 }
 
-impl From<&str> for InputModel {
-    fn from(s: &str) -> Self {
-        let reports = input_as_lines(s).iter()
-            .map(|a| Report::from(a.as_str()))
-            .collect::<Vec<_>>();
-        InputModel { reports }
+impl TryFrom<&str> for InputModel {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let reports = parse_lines(s, |a| Report::try_from(a))?;
+        Ok(InputModel { reports })
     }
 }
 
-impl From<&str> for Report {
-    fn from(s: &str) -> Self {
+impl TryFrom<&str> for Report {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
         let levels = s.split(" ")
-            .map(|b| b.parse::<i64>().unwrap())
-            .collect::<Vec<_>>();
-        Report { levels }
+            .map(|b| parse_token::<i64>(s, b))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Report { levels })
     }
 }
 
@@ -69,84 +73,79 @@ impl Report {
     }
 }
 
-fn default_input() -> &'static str {
-    include_input!(02)
-}
+impl Solution for Day02 {
+    const DAY: u32 = 2;
 
-pub fn part1() -> String {
-    let model = InputModel::from(default_input());
+    type Model = InputModel;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-    model.reports.iter()
-        .filter(|r| r.is_safe_part1())
-        .count()
-        .to_string()
-}
+    fn default_input() -> &'static str {
+        include_input!(02)
+    }
 
-pub fn part2() -> String {
-    let model = InputModel::from(default_input());
+    fn parse(s: &str) -> Result<InputModel, ParseError> {
+        InputModel::try_from(s)
+    }
 
-    model.reports.iter()
-        .filter(|r| r.is_safe_part2())
-        .count()
-        .to_string()
-}
+    fn part1(model: &InputModel) -> usize {
+        model.reports.iter()
+            .filter(|r| r.is_safe_part1())
+            .count()
+    }
 
-fn main() {
-    println!("{}", part1());
-    println!("{}", part2());
+    fn part2(model: &InputModel) -> usize {
+        model.reports.iter()
+            .filter(|r| r.is_safe_part2())
+            .count()
+    }
 }
 
+day_main!(Day02);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn report_is_safe_part1() {
-        let report = Report::from("7 6 4 2 1");
+        let report = Report::try_from("7 6 4 2 1").unwrap();
         assert!(report.is_safe_part1());
-        let report = Report::from("1 3 6 7 9");
+        let report = Report::try_from("1 3 6 7 9").unwrap();
         assert!(report.is_safe_part1());
     }
 
     #[test]
     fn report_not_safe_part1() {
-        let report = Report::from("1 2 7 8 9");
+        let report = Report::try_from("1 2 7 8 9").unwrap();
         assert!(!report.is_safe_part1());
-        let report = Report::from("9 7 6 2 1");
+        let report = Report::try_from("9 7 6 2 1").unwrap();
         assert!(!report.is_safe_part1());
-        let report = Report::from("1 3 2 4 5");
+        let report = Report::try_from("1 3 2 4 5").unwrap();
         assert!(!report.is_safe_part1());
-        let report = Report::from("8 6 4 4 1");
+        let report = Report::try_from("8 6 4 4 1").unwrap();
         assert!(!report.is_safe_part1());
     }
 
     #[test]
     fn report_is_safe_part2() {
-        let report = Report::from("7 6 4 2 1");
+        let report = Report::try_from("7 6 4 2 1").unwrap();
         assert!(report.is_safe_part2());
-        let report = Report::from("1 3 2 4 5");
+        let report = Report::try_from("1 3 2 4 5").unwrap();
         assert!(report.is_safe_part2());
-        let report = Report::from("8 6 4 4 1");
+        let report = Report::try_from("8 6 4 4 1").unwrap();
         assert!(report.is_safe_part2());
-        let report = Report::from("1 3 6 7 9");
+        let report = Report::try_from("1 3 6 7 9").unwrap();
         assert!(report.is_safe_part2());
     }
 
     #[test]
     fn report_not_safe_part2() {
-        let report = Report::from("1 2 7 8 9");
+        let report = Report::try_from("1 2 7 8 9").unwrap();
         assert!(!report.is_safe_part2());
-        let report = Report::from("9 7 6 2 1");
+        let report = Report::try_from("9 7 6 2 1").unwrap();
         assert!(!report.is_safe_part2());
     }    
 
-    #[test]
-    fn solve_part1() {
-        assert_eq!(part1(), "269");
-    }
-
-    #[test]
-    fn solve_part2() {
-        assert_eq!(part2(), "337");
-    }
+    answer_tests!(Day02);
 }
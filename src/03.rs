@@ -2,7 +2,9 @@
 use advent::*;
 use regex::Regex;
 
-struct Memory {
+pub struct Day03;
+
+pub struct Memory {
     line: String,
 }
 
@@ -13,7 +15,7 @@ struct Multiply {
 
 impl From<&str> for Memory {
     fn from(s: &str) -> Self {
-        Memory { line: s.replace("\n","").to_owned() }
+        Memory { line: input_as_lines(s).concat() }
     }
 }
 
@@ -47,39 +49,46 @@ impl Memory {
             .collect::<Vec<_>>()
     }
 
+    // Operands have one to three digits, so longer numbers are not instructions and
+    // every match fits an i64.
     fn regex_multiply(&self) -> Regex {
-        Regex::new(r"mul\((\d+),(\d+)\)").expect("Invalid regex for multiply")
+        Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").expect("Invalid regex for multiply")
     }
     fn regex_on_off(&self) -> Regex {
         Regex::new(r"don't\(\).*?do\(\)").expect("Invalid regex for on/off")
     }
 }
 
-fn default_input() -> &'static str {
-    include_input!(03)
-}
+impl Solution for Day03 {
+    const DAY: u32 = 3;
 
-pub fn part1() -> String {
-    let mem = Memory::from(default_input());
-    mem.parse_part1().iter()
-        .map(|b| b.x * b.y)
-        .sum::<i64>()
-        .to_string()
-}
+    type Model = Memory;
+    type Answer1 = i64;
+    type Answer2 = i64;
 
-pub fn part2() -> String {
-    let mem = Memory::from(default_input());
-    mem.parse_part2().iter()
-        .map(|b| b.x * b.y)
-        .sum::<i64>()
-        .to_string()
-}
+    fn default_input() -> &'static str {
+        include_input!(03)
+    }
 
-fn main() {
-    println!("{}", part1());
-    println!("{}", part2());
+    fn parse(s: &str) -> Result<Memory, ParseError> {
+        Ok(Memory::from(s))
+    }
+
+    fn part1(model: &Memory) -> i64 {
+        model.parse_part1().iter()
+            .map(|b| b.x * b.y)
+            .sum::<i64>()
+    }
+
+    fn part2(model: &Memory) -> i64 {
+        model.parse_part2().iter()
+            .map(|b| b.x * b.y)
+            .sum::<i64>()
+    }
 }
 
+day_main!(Day03);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,6 +109,15 @@ mod tests {
         assert_eq!(p[3].y, 5);
     }
 
+    #[test]
+    fn operand_digits() {
+        let mem = Memory::from("mul(99999999999999999999,2)mul(1234,5)mul(123,4)");
+
+        let p = mem.parse_part1();
+        assert_eq!(p.len(), 1);
+        assert_eq!((p[0].x, p[0].y), (123, 4));
+    }
+
     #[test]
     fn test_part2() {
         let mem = Memory::from("xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))");
@@ -112,13 +130,5 @@ mod tests {
         assert_eq!(p[1].y, 5);
     }
 
-    #[test]
-    fn solve_part1() {
-        assert_eq!(part1(), "166357705");
-    }
-
-    #[test]
-    fn solve_part2() {
-        assert_eq!(part2(), "88811886");
-    }
+    answer_tests!(Day03);
 }
@@ -1,20 +1,19 @@
 
 use advent::*;
 
-struct WordSearch {
+pub struct Day04;
+
+pub struct WordSearch {
     puzzle: Board2D<char>,
 }
 
-impl From<&str> for WordSearch {
-    fn from(s: &str) -> Self {
-        let lines = input_as_lines(s);
-        let rows = lines.len();
-        let cols = lines[0].len();
-        let vec = lines.iter().flat_map(|x| x.chars()).collect();
+impl TryFrom<&str> for WordSearch {
+    type Error = ParseError;
 
-        WordSearch {
-            puzzle: Board2D::new(vec, rows, cols),
-        }
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Ok(WordSearch {
+            puzzle: Board2D::try_from(s)?,
+        })
     }
 }
 
@@ -27,14 +26,9 @@ impl WordSearch {
             let y = i as i64;
             for j in 0..self.puzzle.width() {
                 let x = j as i64;
-                if self.test_word(x, y, Compass::North, w) { result.push((x, y, Compass::North)); }
-                if self.test_word(x, y, Compass::NorthEast, w) { result.push((x, y, Compass::NorthEast)); }
-                if self.test_word(x, y, Compass::East, w) { result.push((x, y, Compass::East)); }
-                if self.test_word(x, y, Compass::SouthEast, w) { result.push((x, y, Compass::SouthEast)); }
-                if self.test_word(x, y, Compass::South, w) { result.push((x, y, Compass::South)); }
-                if self.test_word(x, y, Compass::SouthWest, w) { result.push((x, y, Compass::SouthWest)); }
-                if self.test_word(x, y, Compass::West, w) { result.push((x, y, Compass::West)); }
-                if self.test_word(x, y, Compass::NorthWest, w) { result.push((x, y, Compass::NorthWest)); }
+                for dir in Compass::all() {
+                    if self.test_word(x, y, dir, w) { result.push((x, y, dir)); }
+                }
             }
         }
 
@@ -70,16 +64,8 @@ impl WordSearch {
                 if word.len() == 1 { return true; }
                 
                 let next = &word[1..];
-                return match dir {
-                    Compass::North => self.test_word(x, y - 1, dir, next),
-                    Compass::NorthEast => self.test_word(x + 1, y - 1, dir, next),
-                    Compass::East => self.test_word(x + 1, y, dir, next),
-                    Compass::SouthEast => self.test_word(x + 1, y + 1, dir, next),
-                    Compass::South => self.test_word(x, y + 1, dir, next),
-                    Compass::SouthWest => self.test_word(x - 1, y + 1, dir, next),
-                    Compass::West => self.test_word(x - 1, y, dir, next),
-                    Compass::NorthWest => self.test_word(x - 1, y - 1, dir, next),
-                };
+                let d = dir.delta();
+                return self.test_word(x + d.x as i64, y + d.y as i64, dir, next);
             }
         } 
 
@@ -87,33 +73,39 @@ impl WordSearch {
     }
 }
 
-fn default_input() -> &'static str {
-    include_input!(04)
-}
+impl Solution for Day04 {
+    const DAY: u32 = 4;
 
-pub fn part1() -> String {
-    let model = WordSearch::from(default_input());
+    type Model = WordSearch;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-    model.find_xmas_part1().len().to_string()
-}
+    fn default_input() -> &'static str {
+        include_input!(04)
+    }
 
-pub fn part2() -> String {
-    let model = WordSearch::from(default_input());
-    model.find_xmas_part2().len().to_string()
-}
+    fn parse(s: &str) -> Result<WordSearch, ParseError> {
+        WordSearch::try_from(s)
+    }
+
+    fn part1(model: &WordSearch) -> usize {
+        model.find_xmas_part1().len()
+    }
 
-fn main() {
-    println!("{}", part1());
-    println!("{}", part2());
+    fn part2(model: &WordSearch) -> usize {
+        model.find_xmas_part2().len()
+    }
 }
 
+day_main!(Day04);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn parse_example() {
-        let ws = WordSearch::from("abc\r\ndef\r\nghi");
+        let ws = WordSearch::try_from("abc\r\ndef\r\nghi").unwrap();
 
         assert_eq!(ws.puzzle.width(), 3);
         assert_eq!(ws.puzzle.height(), 3);
@@ -131,9 +123,16 @@ mod tests {
         assert!(ws.puzzle.index(3, 0).is_none());
     }
 
+    #[test]
+    fn parse_ragged() {
+        let err = WordSearch::try_from("abc\r\nde\r\nghi").err().unwrap();
+        assert_eq!(err.line, 2);
+        assert_eq!(err.text, "de");
+    }
+
     #[test]
     fn parse_input() {
-        let ws = WordSearch::from(default_input());
+        let ws = WordSearch::try_from(Day04::default_input()).unwrap();
 
         assert_eq!(ws.puzzle.width(), 140);
         assert_eq!(ws.puzzle.height(), 140);
@@ -141,7 +140,7 @@ mod tests {
 
     #[test]
     fn find_xmas_part1() {
-        let ws = WordSearch::from("..X...\r\n.SAMX.\r\n.A..A.\r\nXMAS.S\r\n.X....");
+        let ws = WordSearch::try_from("..X...\r\n.SAMX.\r\n.A..A.\r\nXMAS.S\r\n.X....").unwrap();
 
         assert_eq!(ws.puzzle.width(), 6);
         assert_eq!(ws.puzzle.height(), 5);
@@ -166,7 +165,7 @@ mod tests {
 
     #[test]
     fn find_xmas_part2() {
-        let w1 = WordSearch::from("M.S\r\n.A.\r\nM.S");
+        let w1 = WordSearch::try_from("M.S\r\n.A.\r\nM.S").unwrap();
 
         assert!(w1.test_word(0, 0, Compass::SouthEast, "MAS"));
         assert!(!w1.test_word(2, 0, Compass::SouthWest, "MAS"));
@@ -177,19 +176,11 @@ mod tests {
         assert_eq!(f1.len(), 1);
         assert_eq!(f1[0], (0, 0));
         
-        let w2 = WordSearch::from(".M.S......\r\n..A..MSMS.\r\n.M.S.MAA..\r\n..A.ASMSM.\r\n.M.S.M....\r\n..........\r\nS.S.S.S.S.\r\n.A.A.A.A..\r\nM.M.M.M.M.\r\n..........");
+        let w2 = WordSearch::try_from(".M.S......\r\n..A..MSMS.\r\n.M.S.MAA..\r\n..A.ASMSM.\r\n.M.S.M....\r\n..........\r\nS.S.S.S.S.\r\n.A.A.A.A..\r\nM.M.M.M.M.\r\n..........").unwrap();
 
         let f2 = w2.find_xmas_part2();
         assert_eq!(f2.len(), 9);
     }
 
-    #[test]
-    fn solve_part1() {
-        assert_eq!(part1(), "2536");
-    }
-
-    #[test]
-    fn solve_part2() {
-        assert_eq!(part2(), "1875");
-    }
+    answer_tests!(Day04);
 }
@@ -1,9 +1,14 @@
 
 use advent::*;
+use std::sync::OnceLock;
 
-struct SafetyManual {
+pub struct Day05;
+
+pub struct SafetyManual {
     rules: Vec<SafetyManualRule>,
     updates: Vec<SafetyManualUpdate>,
+    /// Whether each update is in order, shared by both parts.
+    ordered: OnceLock<Vec<bool>>,
 }
 
 struct SafetyManualRule {
@@ -49,10 +54,17 @@ impl SafetyManual {
         }
     }
 
+    fn ordered(&self) -> &[bool] {
+        self.ordered.get_or_init(|| {
+            self.updates.iter().map(|x| self.in_order(x.pages.as_slice())).collect()
+        })
+    }
+
     fn sum_part1(&self) -> i64 {
         self.updates.iter()
-        .filter(|x| self.in_order(x.pages.as_slice()))
-        .map(|x| &x.pages)
+        .zip(self.ordered())
+        .filter(|(_, ordered)| **ordered)
+        .map(|(x, _)| &x.pages)
         //.inspect(|p| println!("{:?}", p))
         .map(|p| p[p.len() / 2])
         //.inspect(|p| println!("{:?}", p))
@@ -61,8 +73,9 @@ impl SafetyManual {
 
     fn sum_part2(&self) -> i64 {
         self.updates.iter()
-        .filter(|x| !self.in_order(x.pages.as_slice()))
-        .map(|x| &x.pages)
+        .zip(self.ordered())
+        .filter(|(_, ordered)| !**ordered)
+        .map(|(x, _)| &x.pages)
         //.inspect(|p| println!("{:?}", p))
         .map(|p| {
             let mut pages = p.clone();
@@ -75,70 +88,85 @@ impl SafetyManual {
     }
 }
 
-impl From<&str> for SafetyManual {
-    fn from(s: &str) -> Self {
-        let lines = input_as_lines(s);
-
-        let rules = lines.iter()
-            .filter(|x| x.contains("|"))
-            .map(|x| SafetyManualRule::from(x.as_str()))
-            .collect();
-
-        let updates = lines.iter()
-            .filter(|x| x.contains(","))
-            .map(|x| SafetyManualUpdate::from(x.as_str()))
-            .collect();
-
-        SafetyManual { rules, updates }
+impl TryFrom<&str> for SafetyManual {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let sections = input_as_sections(s);
+
+        match &sections[..] {
+            [rules, updates] => Ok(SafetyManual {
+                rules: rules.parse_lines(|x| SafetyManualRule::try_from(x))?,
+                updates: updates.parse_lines(|x| SafetyManualUpdate::try_from(x))?,
+                ordered: OnceLock::new(),
+            }),
+            _ => {
+                let msg = format!("Expected rules and updates separated by a blank line, found {} sections", sections.len());
+                Err(ParseError::new(msg, ""))
+            },
+        }
     }
 }
 
-impl From<&str> for SafetyManualRule {
-    fn from(s: &str) -> Self {
+impl TryFrom<&str> for SafetyManualRule {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
         let parts = s.split("|").collect::<Vec<_>>();
         if let [left, right] = parts[..] {
-            let left = left.parse::<i64>().expect("Unable to parse left side");
-            let right = right.parse::<i64>().expect("Unable to parse right side");
-            return SafetyManualRule { left, right };
+            let left = parse_token::<i64>(s, left)?;
+            let right = parse_token::<i64>(s, right)?;
+            return Ok(SafetyManualRule { left, right });
         }
-        
-        panic!("Unable to parse rule: {}", s);
+
+        Err(ParseError::new("Unable to parse rule", s).with_column(1))
     }
 }
 
-impl From<&str> for SafetyManualUpdate {
-    fn from(s: &str) -> Self {
-        let pages = s.split(",").map(|x| x.parse::<i64>().expect("Unable to parse page")).collect();
-        SafetyManualUpdate { pages }
+impl TryFrom<&str> for SafetyManualUpdate {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let pages = s.split(",")
+            .map(|x| parse_token::<i64>(s, x))
+            .collect::<Result<_, _>>()?;
+        Ok(SafetyManualUpdate { pages })
     }
 }
 
-fn default_input() -> &'static str {
-    include_input!(05)
-}
+impl Solution for Day05 {
+    const DAY: u32 = 5;
 
-pub fn part1() -> String {
-    let man = SafetyManual::from(default_input());
-    man.sum_part1().to_string()
-}
+    type Model = SafetyManual;
+    type Answer1 = i64;
+    type Answer2 = i64;
 
-pub fn part2() -> String {
-    let man = SafetyManual::from(default_input());
-    man.sum_part2().to_string()
-}
+    fn default_input() -> &'static str {
+        include_input!(05)
+    }
 
-fn main() {
-    println!("{}", part1());
-    println!("{}", part2());
+    fn parse(s: &str) -> Result<SafetyManual, ParseError> {
+        SafetyManual::try_from(s)
+    }
+
+    fn part1(model: &SafetyManual) -> i64 {
+        model.sum_part1()
+    }
+
+    fn part2(model: &SafetyManual) -> i64 {
+        model.sum_part2()
+    }
 }
 
+day_main!(Day05);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn parse_example() {
-        let model = SafetyManual::from("1|2\r\n3|4\r\n5,6,7\r\n8,9,10");
+        let model = SafetyManual::try_from("1|2\r\n3|4\r\n\r\n5,6,7\r\n8,9,10").unwrap();
         assert_eq!(model.rules.len(), 2);
         assert_eq!(model.updates.len(), 2);
         assert_eq!(model.rules[0].left, 1);
@@ -149,6 +177,16 @@ mod tests {
         assert_eq!(model.updates[1].pages, vec![8, 9, 10]);
     }
 
+    #[test]
+    fn parse_error() {
+        let err = SafetyManual::try_from("1|2\r\n3|x\r\n\r\n5,6,7").err().unwrap();
+        assert_eq!((err.line, err.column), (2, 3));
+
+        let err = SafetyManual::try_from("1|2\r\n\r\n5,6;7").err().unwrap();
+        assert_eq!((err.line, err.column), (3, 3));
+        assert_eq!(err.text, "6;7");
+    }
+
     #[test]
     fn in_order() {
         let rules = "47|53\r\n97|13\r\n97|61\r\n97|47\r\n75|29\r\n61|13\r\n75|53\r\n29|13\r\n97|29\r\n53|29\r\n61|53\r\n97|53\r\n61|29\r\n47|13\r\n75|47\r\n97|75\r\n47|61\r\n75|61\r\n47|29\r\n75|13\r\n53|13\r\n\r\n";
@@ -160,28 +198,28 @@ mod tests {
         let upd5 = "61,13,29";
         let upd6 = "97,13,75,29,47";
 
-        let man1 = SafetyManual::from(format!("{}\r\n{}", rules, upd1).as_str());
+        let man1 = SafetyManual::try_from(format!("{}\r\n{}", rules, upd1).as_str()).unwrap();
         assert!(man1.in_order(man1.updates[0].pages.as_slice()));
         assert_eq!(man1.sum_part1(), 61);
 
-        let man2 = SafetyManual::from(format!("{}\r\n{}", rules, upd2).as_str());
+        let man2 = SafetyManual::try_from(format!("{}\r\n{}", rules, upd2).as_str()).unwrap();
         assert!(man2.in_order(man2.updates[0].pages.as_slice()));
         assert_eq!(man2.sum_part1(), 53);
 
-        let man3 = SafetyManual::from(format!("{}\r\n{}", rules, upd3).as_str());
+        let man3 = SafetyManual::try_from(format!("{}\r\n{}", rules, upd3).as_str()).unwrap();
         assert!(man3.in_order(man3.updates[0].pages.as_slice()));
         assert_eq!(man3.sum_part1(), 29);
         
-        let man4 = SafetyManual::from(format!("{}\r\n{}", rules, upd4).as_str());
+        let man4 = SafetyManual::try_from(format!("{}\r\n{}", rules, upd4).as_str()).unwrap();
         assert!(!man4.in_order(man4.updates[0].pages.as_slice()));
 
-        let man5 = SafetyManual::from(format!("{}\r\n{}", rules, upd5).as_str());
+        let man5 = SafetyManual::try_from(format!("{}\r\n{}", rules, upd5).as_str()).unwrap();
         assert!(!man5.in_order(man5.updates[0].pages.as_slice()));
 
-        let man6 = SafetyManual::from(format!("{}\r\n{}", rules, upd6).as_str());
+        let man6 = SafetyManual::try_from(format!("{}\r\n{}", rules, upd6).as_str()).unwrap();
         assert!(!man6.in_order(man6.updates[0].pages.as_slice()));
 
-        let man7 = SafetyManual::from(format!("{}\r\n{}\r\n{}\r\n{}\r\n{}\r\n{}\r\n{}", rules, upd1, upd2, upd3, upd4, upd5, upd6).as_str());
+        let man7 = SafetyManual::try_from(format!("{}\r\n{}\r\n{}\r\n{}\r\n{}\r\n{}\r\n{}", rules, upd1, upd2, upd3, upd4, upd5, upd6).as_str()).unwrap();
         assert_eq!(man7.sum_part1(), 61 + 53 + 29);
     }
 
@@ -196,32 +234,24 @@ mod tests {
         let upd5 = "61,13,29";
         let upd6 = "97,13,75,29,47";
 
-        let man4 = SafetyManual::from(format!("{}\r\n{}", rules, upd4).as_str());
+        let man4 = SafetyManual::try_from(format!("{}\r\n{}", rules, upd4).as_str()).unwrap();
         let mut pages4 = man4.updates[0].pages.clone();
         man4.fix_order(&mut pages4);
         assert_eq!(pages4, vec![97, 75, 47, 61, 53]);
 
-        let man5 = SafetyManual::from(format!("{}\r\n{}", rules, upd5).as_str());
+        let man5 = SafetyManual::try_from(format!("{}\r\n{}", rules, upd5).as_str()).unwrap();
         let mut pages5 = man5.updates[0].pages.clone();
         man5.fix_order(&mut pages5);
         assert_eq!(pages5, vec![61, 29, 13]);
 
-        let man6 = SafetyManual::from(format!("{}\r\n{}", rules, upd6).as_str());
+        let man6 = SafetyManual::try_from(format!("{}\r\n{}", rules, upd6).as_str()).unwrap();
         let mut pages6 = man6.updates[0].pages.clone();
         man6.fix_order(&mut pages6);
         assert_eq!(pages6, vec![97, 75, 47, 29, 13]);
 
-        let man7 = SafetyManual::from(format!("{}\r\n{}\r\n{}\r\n{}\r\n{}\r\n{}\r\n{}", rules, upd1, upd2, upd3, upd4, upd5, upd6).as_str());
+        let man7 = SafetyManual::try_from(format!("{}\r\n{}\r\n{}\r\n{}\r\n{}\r\n{}\r\n{}", rules, upd1, upd2, upd3, upd4, upd5, upd6).as_str()).unwrap();
         assert_eq!(man7.sum_part2(), 47 + 29 + 47);
     }
 
-    #[test]
-    fn solve_part1() {
-        assert_eq!(part1(), "5275");
-    }
-
-    #[test]
-    fn solve_part2() {
-        assert_eq!(part2(), "6191");
-    }
+    answer_tests!(Day05);
 }
@@ -1,170 +1,340 @@
 
 use advent::*;
-use std::collections::{HashSet, HashMap};
+use std::fmt;
+use std::sync::OnceLock;
 
-#[derive(Debug, Default, Clone)]
-struct NorthPoleMap {
-    chars: HashMap<Point32, char>,
-    width: i32,
-    height: i32,
+pub struct Day06;
+
+#[derive(Debug, Clone)]
+pub struct NorthPoleMap {
+    chars: Board2D<char>,
 }
 
-#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
-struct NorthPoleGuard {
-    pos: Point32,
-    dir: Compass,
+#[derive(Debug, Default, Eq, PartialEq, Hash, Copy, Clone)]
+pub struct NorthPoleGuard {
+    pub pos: Point32,
+    pub dir: Compass,
 }
 
-#[derive(Debug, Default)]
-struct NorthPoleLab {
-    map: NorthPoleMap,
-    guard: NorthPoleGuard
+/// How the guard reached a state.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum GuardAction {
+    Start,
+    Moved,
+    Turned,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct GuardStep {
+    pub guard: NorthPoleGuard,
+    pub action: GuardAction,
 }
 
+/// Steps the guard across a map, optionally with one extra obstruction, until it
+/// leaves the map or repeats a state. Records the path as it is iterated, and
+/// displays it drawn over the map as in the puzzle, for debugging.
 #[derive(Debug)]
-enum NorthPoleErr {
-    CycleDetected,
+pub struct GuardSim<'a> {
+    map: &'a NorthPoleMap,
+    obstruction: Option<Point32>,
+    next: Option<GuardStep>,
+    /// Made on the first `next`, so simulators used only to `step` stay cheap.
+    visited: Option<VisitedSet>,
+    path: Vec<NorthPoleGuard>,
+    turns: Vec<Point32>,
+    exit: Option<Point32>,
+    looped: bool,
 }
 
-impl NorthPoleMap {
-    fn obstruct_at(&self, p: Point32) -> Self {
-        let mut result = self.clone();
-        result.chars.insert(p, '#');
-        result
-    }
+/// A finished walk: every state in order, and the same states as a set.
+#[derive(Debug)]
+struct GuardWalk {
+    path: Vec<NorthPoleGuard>,
+    visited: VisitedSet,
+}
+
+/// For each cell and cardinal direction, where the guard stops walking straight:
+/// the last cell before an obstruction, or `None` when it walks off the map.
+#[derive(Debug)]
+struct JumpTable {
+    stops: Board2D<[Option<Point32>; 4]>,
+}
+
+#[derive(Debug)]
+pub struct NorthPoleLab {
+    map: NorthPoleMap,
+    guard: NorthPoleGuard,
+    /// The unobstructed walk from part 1, reused by part 2.
+    walk: OnceLock<GuardWalk>,
 }
 
 impl NorthPoleGuard {
     fn next_pos(&self) -> Point32 {
-        let mut result = self.pos;
-        match self.dir {
-            Compass::North => result.y -= 1,
-            Compass::East => result.x += 1,
-            Compass::South => result.y += 1,
-            Compass::West => result.x -= 1,
-            _ => (),
-        };
-        result
+        self.pos + self.dir.delta()
     }
 }
 
-impl NorthPoleLab {
-    fn walk_key(&self, pos: &Point32, dir: &Compass, part: u32) -> i32 {
-        match part {
-            1 => pos.y | pos.x << 10,
-            _ => pos.y | pos.x << 10 | (*dir as i32) << 20,
+impl<'a> GuardSim<'a> {
+    pub fn new(map: &'a NorthPoleMap, guard: NorthPoleGuard) -> Self {
+        GuardSim {
+            map,
+            obstruction: None,
+            next: Some(GuardStep { guard, action: GuardAction::Start }),
+            visited: None,
+            path: vec![],
+            turns: vec![],
+            exit: None,
+            looped: false,
         }
     }
 
-    fn walk_part1(&self) -> usize {
-        self.walk_impl(&self.map, 1).expect("Part 1 should not fail").len()
+    /// Treats `p` as an obstruction as well as the map's own.
+    pub fn with_obstruction(mut self, p: Point32) -> Self {
+        self.obstruction = Some(p);
+        self
     }
 
-    fn walk_part2(&self) -> usize {
-        let pt1 = self.walk_impl(&self.map, 1).expect("Part 1 should not fail");
-
-        let mut count: usize = 0;
-        for y in 0..self.map.height - 1 {
-            //println!("Row={}", y);
-            for x in 0..self.map.width - 1 {
-                let p = Point32 { x, y };
-
-                // Optimisation: only consider points from part 1
-                let key = self.walk_key(&p, &Compass::North, 1);
-                if !pt1.contains(&key) {
-                    continue;
-                }
-
-                let c = self.map.chars[&p];
-                if c != '#' && c != '^' {
-                    let map = self.map.obstruct_at(p);
-                    if self.walk_impl(&map, 2).is_err() {
-                        count += 1;
-                    }
-                }
-            }
+    /// The guard after one turn or move from `guard`, or `None` once it walks off the map.
+    /// Does not record anything, so suits cycle detection over `guard` alone.
+    pub fn step(&self, guard: &NorthPoleGuard) -> Option<NorthPoleGuard> {
+        let ahead = guard.next_pos();
+        match *self.map.chars.get(ahead)? == '#' || matches!(self.obstruction, Some(o) if o == ahead) {
+            true => Some(NorthPoleGuard { pos: guard.pos, dir: guard.dir.cardinal_right() }),
+            false => Some(NorthPoleGuard { pos: ahead, dir: guard.dir }),
         }
-        count
     }
 
-    fn walk_impl(&self, map: &NorthPoleMap, part: u32) -> Result<HashSet<i32>, NorthPoleErr> {
-        let mut gcurr = self.guard;
-        let mut walk = HashSet::new();
-        walk.insert(self.walk_key(&gcurr.pos, &gcurr.dir, part));
+    /// Every state yielded so far, in order.
+    pub fn path(&self) -> &[NorthPoleGuard] {
+        &self.path
+    }
 
-        loop {
-            let mut gnext = gcurr;
-            gnext.pos = gnext.next_pos();
+    /// The positions where the guard turned, in order.
+    pub fn turns(&self) -> &[Point32] {
+        &self.turns
+    }
 
-            if map.chars.contains_key(&gnext.pos) {
-                //println!("{:?}: ch={} (dir={:?})", next, ch[&next], dir);
-                if map.chars[&gnext.pos] == '#' {
-                    gcurr.dir = gcurr.dir.cardinal_right();
-                } else {
-                    let key = self.walk_key(&gnext.pos, &gnext.dir, part);
-                    if part == 2 && walk.contains(&key) {
-                        return Err(NorthPoleErr::CycleDetected);
-                    }
+    /// The last position before the guard walked off the map, once it has.
+    pub fn exit(&self) -> Option<Point32> {
+        self.exit
+    }
 
-                    walk.insert(key);
-                    gcurr.pos = gnext.pos;
-                }
+    /// Whether the guard came back to a state it had already been in.
+    pub fn looped(&self) -> bool {
+        self.looped
+    }
 
-                continue;
+    /// The guard at its next turn, or `None` once it walks off the map, taking the
+    /// map's obstructions from `jumps` and checking only the extra one. As `step`,
+    /// but many cells at a time; inlined as the hot loop of part 2.
+    #[inline]
+    fn jump(&self, jumps: &JumpTable, guard: &NorthPoleGuard) -> Option<NorthPoleGuard> {
+        let stop = jumps.stops[guard.pos][guard.dir.index() / 2];
+        let turn = |pos| Some(NorthPoleGuard { pos, dir: guard.dir.cardinal_right() });
+
+        if let Some(o) = self.obstruction {
+            let delta = guard.dir.delta();
+            let ahead = (o - guard.pos).x * delta.x + (o - guard.pos).y * delta.y;
+            let on_line = ahead > 0 && guard.pos.step(guard.dir, ahead) == o;
+            if on_line && stop.map_or(true, |x| x.manhattan(guard.pos) >= ahead) {
+                return turn(o - delta);
             }
-
-            break;
         }
 
-        Ok(walk)
-    }
-}
-
-impl From<&str> for NorthPoleLab {
-    fn from(s: &str) -> Self {
-        let lines = input_as_lines(s);
-        let mut map = NorthPoleMap::default();
-        let mut guard = NorthPoleGuard::default();
-
-        map.height = lines.len() as i32;
-        map.width = if lines.is_empty() { 0 } else { lines[0].len() } as i32;
-        for (ey, line) in lines.iter().enumerate() {
-            let y = ey as i32;
-            for (ex, c) in line.chars().enumerate() {
-                let x = ex as i32;
-                if c == '^' {
-                    guard.pos = Point32 { x, y };
-                }
-                map.chars.insert(Point32 { x, y }, match c {
-                    '^' => '.',
-                    _ => c,
-                });
+        stop.and_then(turn)
+    }
+
+    fn into_walk(self) -> GuardWalk {
+        let visited = self.visited.unwrap_or_else(|| VisitedSet::for_board(&self.map.chars));
+        GuardWalk { path: self.path, visited }
+    }
+}
+
+impl JumpTable {
+    fn new(map: &NorthPoleMap) -> Self {
+        let chars = &map.chars;
+        let points = chars.points().collect::<Vec<_>>();
+        let mut stops = Board2D::filled([None; 4], chars.height(), chars.width());
+
+        for dir in Compass::cardinals() {
+            let i = dir.index() / 2;
+            // Visit the cell ahead before each cell, so its stop is already known.
+            let reversed = matches!(dir, Compass::South | Compass::East);
+            for k in 0..points.len() {
+                let p = points[if reversed { points.len() - 1 - k } else { k }];
+                let ahead = p.step(dir, 1);
+                stops[p][i] = match chars.get(ahead) {
+                    None => None,
+                    Some('#') => Some(p),
+                    Some(_) => stops[ahead][i],
+                };
             }
         }
 
-        NorthPoleLab { map, guard, }
+        JumpTable { stops }
+    }
+}
+
+impl Iterator for GuardSim<'_> {
+    type Item = GuardStep;
+
+    fn next(&mut self) -> Option<GuardStep> {
+        let step = self.next.take()?;
+        let guard = step.guard;
+        let visited = self.visited.get_or_insert_with(|| VisitedSet::for_board(&self.map.chars));
+        if !visited.insert(guard.pos, guard.dir) {
+            self.looped = true;
+            return None;
+        }
+
+        self.path.push(guard);
+        if step.action == GuardAction::Turned {
+            self.turns.push(guard.pos);
+        }
+        self.next = self.step(&guard).map(|next| GuardStep {
+            guard: next,
+            action: if next.pos == guard.pos { GuardAction::Turned } else { GuardAction::Moved },
+        });
+        if self.next.is_none() {
+            self.exit = Some(guard.pos);
+        }
+
+        Some(step)
     }
 }
 
-fn default_input() -> &'static str {
-    include_input!(06)
+impl fmt::Display for GuardSim<'_> {
+    /// Draws the walk so far as in the puzzle: `|` and `-` for moves, `+` for turns
+    /// and crossings, and `O` for the extra obstruction.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut chars = self.map.chars.clone();
+        for guard in self.path() {
+            let mark = match guard.dir {
+                Compass::North | Compass::South => '|',
+                _ => '-',
+            };
+            let cell = &mut chars[guard.pos];
+            *cell = match *cell {
+                '.' => mark,
+                c if c == mark => mark,
+                _ => '+',
+            };
+        }
+        for p in self.turns() {
+            chars[*p] = '+';
+        }
+        if let Some(p) = self.obstruction {
+            chars.set(p, 'O');
+        }
+
+        for row in chars.rows() {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+        match self.exit() {
+            Some(p) => write!(f, "Leaves at ({}, {})", p.x, p.y),
+            None if self.looped() => write!(f, "Loops"),
+            None => write!(f, "Walking"),
+        }
+    }
 }
 
-pub fn part1() -> String {
-    let lab = NorthPoleLab::from(default_input());
-    lab.walk_part1().to_string()
+impl NorthPoleLab {
+    /// A simulator for the guard from its starting state.
+    pub fn sim(&self) -> GuardSim<'_> {
+        GuardSim::new(&self.map, self.guard)
+    }
+
+    fn walk(&self) -> &GuardWalk {
+        self.walk.get_or_init(|| {
+            let mut sim = self.sim();
+            sim.by_ref().for_each(drop);
+            assert!(!sim.looped(), "The unobstructed guard should leave the map");
+            sim.into_walk()
+        })
+    }
+
+    fn walk_part1(&self) -> usize {
+        self.walk().visited.points().count()
+    }
+
+
+    /// Serial, as `aoc --jobs` and `bench` already run days on threads of their own;
+    /// `count_loops` takes more jobs when called on its own.
+    fn walk_part2(&self) -> usize {
+        self.count_loops(1)
+    }
+
+    /// Each point on the part 1 walk where an obstruction would change the guard's
+    /// path, with the guard just before it first reaches that point. The walk up to
+    /// there is unchanged, so simulating from that guard gives the same result.
+    fn obstruction_candidates(&self) -> Vec<(Point32, NorthPoleGuard)> {
+        let mut seen = VisitedSet::for_board(&self.map.chars);
+        seen.insert(self.guard.pos, Compass::North);
+
+        self.walk().path.windows(2)
+            .filter(|w| w[0].pos != w[1].pos && seen.insert(w[1].pos, Compass::North))
+            .map(|w| (w[1].pos, w[0]))
+            .collect()
+    }
+
+    /// The number of obstructions that trap the guard in a loop, checking candidates
+    /// on `jobs` threads and following the guard from turn to turn.
+    fn count_loops(&self, jobs: usize) -> usize {
+        let jumps = JumpTable::new(&self.map);
+        let loops = map_parallel(self.obstruction_candidates(), jobs, |(p, before)| {
+            let sim = self.sim().with_obstruction(p);
+            brent(before, |g| sim.jump(&jumps, g)).is_some()
+        });
+        loops.into_iter().filter(|x| *x).count()
+    }
 }
 
-pub fn part2() -> String {
-    let lab = NorthPoleLab::from(default_input());
-    lab.walk_part2().to_string()
+impl TryFrom<&str> for NorthPoleLab {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let mut guard = None;
+        let chars = Board2D::try_parse(s, |c, pos| match c {
+            '^' | '>' | 'v' | '<' if guard.is_some() => Err(ParseError::new("Found more than one guard", &c.to_string())),
+            '^' | '>' | 'v' | '<' => {
+                guard = Some(NorthPoleGuard { pos, dir: Compass::try_from(c)? });
+                Ok('.')
+            },
+            _ => Ok(c),
+        })?;
+
+        let guard = guard.ok_or_else(|| ParseError::new("No guard found in map", ""))?;
+        let map = NorthPoleMap { chars };
+        Ok(NorthPoleLab { map, guard, walk: OnceLock::new() })
+    }
 }
 
-fn main() {
-    println!("{}", part1());
-    println!("{}", part2());
+impl Solution for Day06 {
+    const DAY: u32 = 6;
+
+    type Model = NorthPoleLab;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn default_input() -> &'static str {
+        include_input!(06)
+    }
+
+    fn parse(s: &str) -> Result<NorthPoleLab, ParseError> {
+        NorthPoleLab::try_from(s)
+    }
+
+    fn part1(model: &NorthPoleLab) -> usize {
+        model.walk_part1()
+    }
+
+    fn part2(model: &NorthPoleLab) -> usize {
+        model.walk_part2()
+    }
 }
 
+day_main!(Day06);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -173,43 +343,122 @@ mod tests {
     fn parse_example() {
         let input = "....#.....\r\n.........#\r\n..........\r\n..#.......\r\n.......#..\r\n..........\r\n.#..^.....\r\n........#.\r\n#.........\r\n......#...";
 
-        let lab = NorthPoleLab::from(input);
-        assert_eq!(lab.map.width, 10);
-        assert_eq!(lab.map.height, 10);
+        let lab = NorthPoleLab::try_from(input).unwrap();
+        assert_eq!(lab.map.chars.width(), 10);
+        assert_eq!(lab.map.chars.height(), 10);
         assert_eq!(lab.guard.pos.x, 4);
         assert_eq!(lab.guard.pos.y, 6);
-        assert_eq!(lab.map.chars[&Point32 { x: 0, y: 0 }], '.');
-        assert_eq!(lab.map.chars[&Point32 { x: 4, y: 0 }], '#');
+        assert_eq!(lab.map.chars[Point32 { x: 0, y: 0 }], '.');
+        assert_eq!(lab.map.chars[Point32 { x: 4, y: 0 }], '#');
 
         let walk = lab.walk_part1();
         assert_eq!(walk, 41);
     }
 
+    #[test]
+    fn parse_error() {
+        let err = NorthPoleLab::try_from("....\r\n.#..\r\n....").err().unwrap();
+        assert_eq!(err.line, 0);
+
+        let err = NorthPoleLab::try_from("....\r\n.#^.\r\n...v").err().unwrap();
+        assert_eq!((err.line, err.column), (3, 4));
+
+        let err = NorthPoleLab::try_from("....\r\n.#^\r\n....").err().unwrap();
+        assert_eq!(err.line, 2);
+    }
+
     #[test]
     fn walk_part1() {
         let input = "....\r\n.#..\r\n.^..\r\n....";
 
-        let lab = NorthPoleLab::from(input);
+        let lab = NorthPoleLab::try_from(input).unwrap();
         let walk = lab.walk_part1();
         assert_eq!(walk, 3);
+
+        let input = "....\r\n.#..\r\n.>..\r\n....";
+
+        let lab = NorthPoleLab::try_from(input).unwrap();
+        assert_eq!(lab.guard.dir, Compass::East);
+        assert_eq!(lab.walk_part1(), 3);
     }
 
     #[test]
     fn walk_part2() {
         let input = ".#......\r\n.......#\r\n...#....\r\n.....#..\r\no.o^....\r\n....#...\r\n#.......\r\n......#.\r\n";
 
-        let lab = NorthPoleLab::from(input);
+        let lab = NorthPoleLab::try_from(input).unwrap();
+        assert!(lab.walk.get().is_none());
         let walk = lab.walk_part2();
         assert_eq!(walk, 2);
+
+        // Part 2 leaves the part 1 walk behind for part 1 to reuse.
+        assert_eq!(lab.walk.get().map(|x| x.visited.points().count()), Some(lab.walk_part1()));
     }
 
     #[test]
-    fn solve_part1() {
-        assert_eq!(part1(), "5312");
+    fn guard_sim() {
+        let lab = NorthPoleLab::try_from("....\r\n.#..\r\n.^..\r\n....").unwrap();
+        let mut sim = lab.sim();
+        let actions = sim.by_ref().map(|x| x.action).collect::<Vec<_>>();
+        assert_eq!(actions, vec![GuardAction::Start, GuardAction::Turned, GuardAction::Moved, GuardAction::Moved]);
+        assert_eq!(sim.path().last(), Some(&NorthPoleGuard { pos: Point32 { x: 3, y: 2 }, dir: Compass::East }));
+        assert_eq!(sim.turns(), &[Point32 { x: 1, y: 2 }]);
+        assert_eq!(sim.exit(), Some(Point32 { x: 3, y: 2 }));
+        assert!(!sim.looped());
+
+        // The extra obstruction turns the guard south instead, without touching the map.
+        let mut sim = lab.sim().with_obstruction(Point32 { x: 3, y: 2 });
+        assert_eq!(sim.by_ref().last().map(|x| x.guard.dir), Some(Compass::South));
+        assert_eq!(sim.turns(), &[Point32 { x: 1, y: 2 }, Point32 { x: 2, y: 2 }]);
+        assert_eq!(sim.exit(), Some(Point32 { x: 2, y: 3 }));
+        assert_eq!(lab.map.chars[Point32 { x: 3, y: 2 }], '.');
+
+        let input = "....#.....\n.........#\n..........\n..#.......\n.......#..\n..........\n.#..^.....\n........#.\n#.........\n......#...";
+        let lab = NorthPoleLab::try_from(input).unwrap();
+        let mut sim = lab.sim().with_obstruction(Point32 { x: 3, y: 6 });
+        let steps = sim.by_ref().count();
+        assert!(sim.looped() && sim.exit().is_none());
+        assert_eq!(sim.path().len(), steps);
+        assert_eq!(sim.turns().len(), 3);
+
+        let expected = "....#.....\n....+---+#\n....|...|.\n..#.|...|.\n....|..#|.\n....|...|.\n.#.O+---+.\n........#.\n#.........\n......#...\nLoops";
+        assert_eq!(sim.to_string(), expected);
     }
 
     #[test]
-    fn solve_part2() {
-        assert_eq!(part2(), "1748");
+    fn loop_detection() {
+        let input = "....#.....\n.........#\n..........\n..#.......\n.......#..\n..........\n.#..^.....\n........#.\n#.........\n......#...";
+        let lab = NorthPoleLab::try_from(input).unwrap();
+        let sim = lab.sim();
+        assert!(brent(lab.guard, |g| sim.step(g)).is_none());
+
+        let sim = lab.sim().with_obstruction(Point32 { x: 3, y: 6 });
+        let cycle = brent(lab.guard, |g| sim.step(g)).unwrap();
+        // The loop passes back through where the guard started.
+        assert_eq!((cycle.start, cycle.prefix), (lab.guard, 0));
+        assert_eq!(find_cycle(lab.guard, |g| sim.step(g)), Some(cycle));
     }
+
+    #[test]
+    fn jump_table() {
+        let input = "....#.....\n.........#\n..........\n..#.......\n.......#..\n..........\n.#..^.....\n........#.\n#.........\n......#...";
+        let lab = NorthPoleLab::try_from(input).unwrap();
+        let jumps = JumpTable::new(&lab.map);
+        let pt = |x, y| Point32 { x, y };
+        assert_eq!(jumps.stops[pt(4, 6)], [Some(pt(4, 1)), None, None, Some(pt(2, 6))]);
+        assert_eq!(jumps.stops[pt(0, 9)], [Some(pt(0, 9)), Some(pt(5, 9)), None, None]);
+
+        // Jumping turn to turn agrees with stepping cell by cell, with and without obstructions.
+        let candidates = lab.obstruction_candidates();
+        assert_eq!(candidates.len(), 40);
+        for (p, before) in candidates {
+            let sim = lab.sim().with_obstruction(p);
+            let stepped = brent(before, |g| sim.step(g)).is_some();
+            assert_eq!(brent(before, |g| sim.jump(&jumps, g)).is_some(), stepped, "{:?}", p);
+        }
+        assert_eq!(lab.count_loops(1), 6);
+        assert_eq!(lab.count_loops(4), 6);
+    }
+
+    answer_tests!(Day06);
 }
@@ -1,7 +1,9 @@
 
 use advent::*;
 
-struct BridgeCalibration {
+pub struct Day07;
+
+pub struct BridgeCalibration {
     equations: Vec<BridgeEquation>,
 }
 
@@ -10,21 +12,29 @@ struct BridgeEquation {
     values: Vec<i64>,
 }
 
-impl From<&str> for BridgeCalibration {
-    fn from(s: &str) -> Self {
-        let equations = input_as_lines(s).iter()
-            .map(|a| BridgeEquation::from(a.as_str()))
-            .collect::<Vec<_>>();
-        BridgeCalibration { equations }
+impl TryFrom<&str> for BridgeCalibration {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let equations = parse_lines(s, |a| BridgeEquation::try_from(a))?;
+        Ok(BridgeCalibration { equations })
     }
 }
 
-impl From<&str> for BridgeEquation {
-    fn from(s: &str) -> Self {
-        let s = s.split(": ").collect::<Vec<_>>();
-        let result = s[0].parse::<i64>().unwrap();
-        let values = s[1].split(" ").map(|x| x.parse::<i64>().unwrap()).collect();
-        BridgeEquation { result, values }
+impl TryFrom<&str> for BridgeEquation {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let (result, operands) = s.split_once(": ")
+            .ok_or_else(|| ParseError::new("Expected '<result>: <values>'", s).with_column(1))?;
+        let result = parse_token::<i64>(s, result)?;
+        let values = operands.split(" ")
+            .map(|x| parse_token::<i64>(s, x))
+            .collect::<Result<Vec<_>, _>>()?;
+        if values.len() < 2 {
+            return Err(ParseError::at_token("Expected at least two values", s, operands));
+        }
+        Ok(BridgeEquation { result, values })
     }
 }
 
@@ -74,53 +84,70 @@ impl BridgeEquation {
     }
 }
 
-fn default_input() -> &'static str {
-    include_input!(07)
-}
+impl Solution for Day07 {
+    const DAY: u32 = 7;
 
-pub fn part1() -> String {
-    let model = BridgeCalibration::from(default_input());
+    type Model = BridgeCalibration;
+    type Answer1 = i64;
+    type Answer2 = i64;
 
-    model.equations.iter()
-        .filter(|x| x.calc_part1() > 0)
-        .map(|x| x.result)
-        .sum::<i64>()
-        .to_string()
-}
+    fn default_input() -> &'static str {
+        include_input!(07)
+    }
 
-pub fn part2() -> String {
-    let model = BridgeCalibration::from(default_input());
+    fn parse(s: &str) -> Result<BridgeCalibration, ParseError> {
+        BridgeCalibration::try_from(s)
+    }
 
-    model.equations.iter()
-        .filter(|x| x.calc_part2() > 0)
-        .map(|x| x.result)
-        .sum::<i64>()
-        .to_string()
-}
+    fn part1(model: &BridgeCalibration) -> i64 {
+        model.equations.iter()
+            .filter(|x| x.calc_part1() > 0)
+            .map(|x| x.result)
+            .sum::<i64>()
+    }
 
-fn main() {
-    println!("{}", part1());
-    println!("{}", part2());
+    fn part2(model: &BridgeCalibration) -> i64 {
+        model.equations.iter()
+            .filter(|x| x.calc_part2() > 0)
+            .map(|x| x.result)
+            .sum::<i64>()
+    }
 }
 
+day_main!(Day07);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn parse_example() {
-        let m = BridgeEquation::from("190: 10 19");
+        let m = BridgeEquation::try_from("190: 10 19").unwrap();
         assert_eq!(m.result, 190);
         assert_eq!(m.values, vec![10, 19]);
 
-        let m = BridgeEquation::from("3267: 81 40 27");
+        let m = BridgeEquation::try_from("3267: 81 40 27").unwrap();
         assert_eq!(m.result, 3267);
         assert_eq!(m.values, vec![81, 40, 27]);
     }
 
+    #[test]
+    fn parse_error() {
+        let err = BridgeCalibration::try_from("190: 10 19\r\n3267 81 40 27").err().unwrap();
+        assert_eq!((err.line, err.column), (2, 1));
+
+        let err = BridgeCalibration::try_from("190: 10 19\r\n3267: 81 4o 27").err().unwrap();
+        assert_eq!((err.line, err.column), (2, 10));
+        assert_eq!(err.text, "4o");
+
+        let err = BridgeCalibration::try_from("190: 10 19\n5: 5").err().unwrap();
+        assert_eq!((err.line, err.column), (2, 4));
+        assert_eq!(err.text, "5");
+    }
+
     #[test]
     fn eval_part1() {
-        let c = BridgeCalibration::from("190: 10 19\r\n3267: 81 40 27\r\n83: 17 5\r\n156: 15 6\r\n7290: 6 8 6 15\r\n161011: 16 10 13\r\n192: 17 8 14\r\n21037: 9 7 18 13\r\n292: 11 6 16 20");
+        let c = BridgeCalibration::try_from(Example::input_of(7, "example1").as_str()).unwrap();
 
         assert_eq!(c.equations.len(), 9);
         assert_eq!(c.equations[0].calc_part1(), 1);
@@ -143,7 +170,7 @@ mod tests {
 
     #[test]
     fn eval_part2() {
-        let c = BridgeCalibration::from("190: 10 19\r\n3267: 81 40 27\r\n83: 17 5\r\n156: 15 6\r\n7290: 6 8 6 15\r\n161011: 16 10 13\r\n192: 17 8 14\r\n21037: 9 7 18 13\r\n292: 11 6 16 20");
+        let c = BridgeCalibration::try_from(Example::input_of(7, "example1").as_str()).unwrap();
 
         assert_eq!(c.equations.len(), 9);
         assert_eq!(c.equations[0].calc_part2(), 1);
@@ -171,13 +198,5 @@ mod tests {
         assert_eq!(BridgeCalibration::op_concat(15, 22), 1522);
     }
 
-    #[test]
-    fn solve_part1() {
-        assert_eq!(part1(), "1620690235709");
-    }
-
-    #[test]
-    fn solve_part2() {
-        assert_eq!(part2(), "145397611075341");
-    }
+    answer_tests!(Day07);
 }
@@ -1,22 +1,22 @@
 
 use advent::*;
 use itertools::Itertools;
-use std::collections::{HashSet, HashMap};
+use std::collections::HashSet;
 
-#[derive(Debug, Default, Clone)]
-struct AntennaMap {
-    chars: HashMap<Point32, char>,
-    width: i32,
-    height: i32,
+pub struct Day08;
+
+#[derive(Debug, Clone)]
+pub struct AntennaMap {
+    chars: Board2D<char>,
 }
 
 impl AntennaMap {
     fn valid_pos(&self, p: Point32) -> bool {
-        p.x >= 0 && p.x < self.width && p.y >= 0 && p.y < self.height
+        self.chars.in_bounds(p)
     }
 
     fn all_freq(&self) -> Vec<char> {
-        self.chars.values()
+        self.chars.iter()
             .filter(|c| **c != '.')
             .copied()
             .unique()
@@ -25,7 +25,7 @@ impl AntennaMap {
     }
 
     fn find_freq(&self, ch: char) -> HashSet<Point32> {
-        self.chars.iter().filter(|(_, c)| **c == ch).map(|(p, _)| *p).collect()
+        self.chars.find_all(&ch).collect()
     }
 
     fn find_antinodes(&self, ch: char, iter: u16) -> HashSet<Point32> {
@@ -38,22 +38,21 @@ impl AntennaMap {
         for comb in freq_comb {
             let f1 = comb[0];
             let f2 = comb[1];
-            let dx = f2.x - f1.x;
-            let dy = f2.y - f1.y;
+            let d = *f2 - *f1;
 
             let mut p1_iter = iter;
-            let mut p1 = Point32 { x: f1.x - dx, y: f1.y - dy };
+            let mut p1 = *f1 - d;
             while p1_iter > 0 && self.valid_pos(p1) {
                 antinodes.insert(p1);
-                p1 = Point32 { x: p1.x - dx, y: p1.y - dy };
+                p1 = p1 - d;
                 p1_iter -= 1;
             }
 
             let mut p2_iter = iter;
-            let mut p2 = Point32 { x: f2.x + dx, y: f2.y + dy };
+            let mut p2 = *f2 + d;
             while p2_iter > 0 && self.valid_pos(p2) {
                 antinodes.insert(p2);
-                p2 = Point32 { x: p2.x + dx, y: p2.y + dy };
+                p2 = p2 + d;
                 p2_iter -= 1;
             }
             if iter > 1 {
@@ -61,7 +60,7 @@ impl AntennaMap {
                 antinodes.insert(*f2);
             }
 
-            //println!("f1=[{},{}]  f2=[{},{}]  d=[{},{}]", f1.x, f1.y, f2.x, f2.y, dx, dy);
+            //println!("f1=[{},{}]  f2=[{},{}]  d=[{},{}]", f1.x, f1.y, f2.x, f2.y, d.x, d.y);
             //println!("p1=[{},{}]  p2=[{},{}]", p1.x, p1.y, p2.x, p2.y);
             //println!("====");
         }
@@ -70,62 +69,55 @@ impl AntennaMap {
     }
 }
 
-impl From<&str> for AntennaMap {
-    fn from(s: &str) -> Self {
-        let lines = input_as_lines(s);
-        let mut map = AntennaMap::default();
-
-        for (ey, line) in lines.iter().enumerate() {
-            let y = ey as i32;
-            for (ex, c) in line.chars().enumerate() {
-                let x = ex as i32;
-                map.chars.insert(Point32 { x, y }, c);
-            }
-        }
+impl TryFrom<&str> for AntennaMap {
+    type Error = ParseError;
 
-        map.width = if lines.is_empty() { 0 } else { lines[0].len() } as i32;
-        map.height = lines.len() as i32;
-
-        map
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Ok(AntennaMap { chars: Board2D::try_from(s)? })
     }
 }
 
-fn default_input() -> &'static str {
-    include_input!(08)
-}
+impl Solution for Day08 {
+    const DAY: u32 = 8;
 
-pub fn part1() -> String {
-    let amap = AntennaMap::from(default_input());
+    type Model = AntennaMap;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-    let mut hset = HashSet::new();
+    fn default_input() -> &'static str {
+        include_input!(08)
+    }
 
-    for freq in amap.all_freq() {
-        for anti in amap.find_antinodes(freq, 1) {
-            hset.insert(anti);
-        }
+    fn parse(s: &str) -> Result<AntennaMap, ParseError> {
+        AntennaMap::try_from(s)
     }
 
-    hset.len().to_string()
-}
+    fn part1(model: &AntennaMap) -> usize {
+        let mut hset = HashSet::new();
+
+        for freq in model.all_freq() {
+            for anti in model.find_antinodes(freq, 1) {
+                hset.insert(anti);
+            }
+        }
 
-pub fn part2() -> String {
-    let amap = AntennaMap::from(default_input());
+        hset.len()
+    }
 
-    let mut hset = HashSet::new();
+    fn part2(model: &AntennaMap) -> usize {
+        let mut hset = HashSet::new();
 
-    for freq in amap.all_freq() {
-        for anti in amap.find_antinodes(freq, 1000) {
-            hset.insert(anti);
+        for freq in model.all_freq() {
+            for anti in model.find_antinodes(freq, 1000) {
+                hset.insert(anti);
+            }
         }
-    }
 
-    hset.len().to_string()
+        hset.len()
+    }
 }
 
-fn main() {
-    println!("{}", part1());
-    println!("{}", part2());
-}
+day_main!(Day08);
 
 #[cfg(test)]
 mod tests {
@@ -133,9 +125,9 @@ mod tests {
 
     #[test]
     fn valid_pos() {
-        let amap = AntennaMap::from(include_input!(08test1));
-        assert_eq!(amap.width, 10);
-        assert_eq!(amap.height, 10);
+        let amap = AntennaMap::try_from(include_input!(08test1)).unwrap();
+        assert_eq!(amap.chars.width(), 10);
+        assert_eq!(amap.chars.height(), 10);
         assert!(amap.valid_pos(Point32 { x: 0, y: 0 }));
         assert!(amap.valid_pos(Point32 { x: 1, y: 0 }));
         assert!(amap.valid_pos(Point32 { x: 0, y: 1 }));
@@ -149,13 +141,13 @@ mod tests {
 
     #[test]
     fn all_freq() {
-        let amap = AntennaMap::from(include_input!(08test1));
+        let amap = AntennaMap::try_from(include_input!(08test1)).unwrap();
         assert_eq!(amap.all_freq(), vec!['#','a']);
     }
 
     #[test]    
     fn find_freq() {
-        let amap = AntennaMap::from(include_input!(08test1));
+        let amap = AntennaMap::try_from(include_input!(08test1)).unwrap();
         let freq = amap.find_freq('a');
         assert_eq!(freq.len(), 3);
         assert!(freq.contains(&Point32 { x: 4, y: 3 }));
@@ -165,7 +157,7 @@ mod tests {
 
     #[test]
     fn find_antinodes_08test1() {
-        let amap = AntennaMap::from(include_input!(08test1));
+        let amap = AntennaMap::try_from(include_input!(08test1)).unwrap();
         let antinodes = amap.find_antinodes('a', 1);
         assert_eq!(antinodes.len(), 4);
         assert!(antinodes.contains(&Point32 { x: 3, y: 1 }));
@@ -176,7 +168,7 @@ mod tests {
 
     #[test]
     fn find_antinodes_08test2() {
-        let amap = AntennaMap::from(include_input!(08test2));
+        let amap = AntennaMap::try_from(include_input!(08test2)).unwrap();
         let antinodes = amap.find_antinodes('T', 1000);
         assert_eq!(antinodes.len(), 9);
         assert!(antinodes.contains(&Point32 { x: 5, y: 0 }));
@@ -193,7 +185,7 @@ mod tests {
 
     #[test]
     fn find_antinodes_08test3() {
-        let amap = AntennaMap::from(include_input!(08test3));
+        let amap = AntennaMap::try_from(include_input!(08test3)).unwrap();
         let mut hset = HashSet::new();
 
         for freq in amap.all_freq() {
@@ -205,13 +197,5 @@ mod tests {
         assert_eq!(hset.len(), 34);
     }
 
-    #[test]
-    fn solve_part1() {
-        assert_eq!(part1(), "351");
-    }
-
-    #[test]
-    fn solve_part2() {
-        assert_eq!(part2(), "1259");
-    }
+    answer_tests!(Day08);
 }
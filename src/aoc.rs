@@ -0,0 +1,138 @@
+use advent::*;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+#[path = "01.rs"] mod day01;
+#[path = "02.rs"] mod day02;
+#[path = "03.rs"] mod day03;
+#[path = "04.rs"] mod day04;
+#[path = "05.rs"] mod day05;
+#[path = "06.rs"] mod day06;
+#[path = "07.rs"] mod day07;
+#[path = "08.rs"] mod day08;
+
+fn registry() -> Registry {
+    Registry::default()
+        .with::<day01::Day01>()
+        .with::<day02::Day02>()
+        .with::<day03::Day03>()
+        .with::<day04::Day04>()
+        .with::<day05::Day05>()
+        .with::<day06::Day06>()
+        .with::<day07::Day07>()
+        .with::<day08::Day08>()
+}
+
+fn usage() -> &'static str {
+    "Usage: aoc [all | <day> | <from>..<to>] [--input <file|-> | --input-dir <dir>]\n       \
+    [--parallel | --jobs <n>] [--bench [--runs <n>] [--format table|json|csv]]"
+}
+
+struct Args {
+    selection: DaySelection,
+    source: InputSource,
+    bench: Option<BenchOptions>,
+    jobs: usize,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let (bench, args) = BenchOptions::from_args(std::env::args().skip(1))?;
+    let (jobs, args) = jobs_from_args(args)?;
+    let (source, args) = InputSource::from_args(args)?;
+    let selection = match args.as_slice() {
+        [] => DaySelection::All,
+        [arg] => DaySelection::try_from(arg.as_str())?,
+        _ => return Err("Too many arguments".to_string()),
+    };
+
+    let single_input = matches!(source, InputSource::File(_) | InputSource::Stdin);
+    if single_input && !matches!(selection, DaySelection::One(_)) {
+        return Err("--input can only be used with a single day".to_string());
+    }
+    if bench.is_some() && jobs > 1 {
+        return Err("--bench runs one day at a time and cannot be combined with --jobs".to_string());
+    }
+
+    Ok(Args { selection, source, bench, jobs })
+}
+
+/// Loads and solves one day, rendering any failure as the message to report.
+fn solve_day(day: &Day, source: &InputSource, parallel: bool) -> Result<DayResult, String> {
+    let input = day.load_input(source)
+        .map_err(|e| format!("Day {:02}: unable to read input: {}", day.day, e))?;
+    day.solve_timed(&input, parallel)
+        .map_err(|e| format!("Day {:02}: {}", day.day, e.diagnostic(&input)))
+}
+
+fn bench_days(days: &[&Day], source: &InputSource, options: &BenchOptions) -> ExitCode {
+    let mut result = ExitCode::SUCCESS;
+    let mut timings = vec![];
+    for day in days {
+        let input = match day.load_input(source) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("Day {:02}: unable to read input: {}", day.day, e);
+                result = ExitCode::FAILURE;
+                continue;
+            }
+        };
+
+        match day.bench(&input, options.runs) {
+            Ok(timing) => timings.push(timing),
+            Err(e) => {
+                eprintln!("Day {:02}: {}", day.day, e.diagnostic(&input));
+                result = ExitCode::FAILURE;
+            }
+        }
+    }
+
+    print!("{}", format_bench(&timings, options.format));
+    result
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n{}", e, usage());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let registry = registry();
+    let days = registry.select(&args.selection);
+    if days.is_empty() {
+        eprintln!("No solutions registered for {:?}", args.selection);
+        return ExitCode::FAILURE;
+    }
+
+    if let Some(bench) = &args.bench {
+        return bench_days(&days, &args.source, bench);
+    }
+
+    let start = Instant::now();
+    let results = map_parallel(days, args.jobs, |day| solve_day(day, &args.source, args.jobs > 1));
+    let wall = start.elapsed();
+
+    let answers = Answers::load_or_default();
+    let name = args.source.name();
+    let mut result = ExitCode::SUCCESS;
+    let mut total = Duration::ZERO;
+    for r in results {
+        match r {
+            Ok(r) => {
+                println!("Day {:02} [{:.3?}, parse {:.3?}]", r.day, r.total_time, r.parse_time);
+                println!("  Part 1: {} ({}) [{:.3?}]", r.part1, answers.check(r.day, &name, 1, &r.part1), r.part1_time);
+                println!("  Part 2: {} ({}) [{:.3?}]", r.part2, answers.check(r.day, &name, 2, &r.part2), r.part2_time);
+                total += r.total_time;
+            },
+            Err(e) => {
+                eprintln!("{}", e);
+                result = ExitCode::FAILURE;
+            }
+        }
+    }
+    println!("Total: {:.3?} elapsed, {:.3?} across days, {} job(s)", wall, total, args.jobs);
+
+    result
+}
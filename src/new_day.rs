@@ -0,0 +1,42 @@
+use advent::*;
+use std::path::Path;
+use std::process::ExitCode;
+
+fn usage() -> &'static str {
+    "Usage: new-day <day>"
+}
+
+fn parse_args() -> Result<u32, String> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match args.as_slice() {
+        [arg] => match DaySelection::try_from(arg.as_str())? {
+            DaySelection::One(day) => Ok(day),
+            _ => Err(format!("Expected a single day, found {}", arg)),
+        },
+        _ => Err("Expected exactly one day".to_string()),
+    }
+}
+
+fn main() -> ExitCode {
+    let day = match parse_args() {
+        Ok(day) => day,
+        Err(e) => {
+            eprintln!("{}\n{}", e, usage());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match scaffold::new_day(Path::new(env!("CARGO_MANIFEST_DIR")), day) {
+        Ok(steps) => {
+            for step in steps {
+                println!("{}", step);
+            }
+            println!("Run `cargo run --bin day{:02}` or `cargo test --bin day{:02}` to get started", day, day);
+            ExitCode::SUCCESS
+        },
+        Err(e) => {
+            eprintln!("Unable to create day {:02}: {}", day, e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,47 +1,49 @@
-
 use advent::*;
 
-struct InputModel {
+pub struct Day00;
+
+pub struct InputModel {
     lines: Vec<String>,
 }
 
-impl From<&str> for InputModel {
-    fn from(s: &str) -> Self {
+impl TryFrom<&str> for InputModel {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
         let lines = input_as_lines(s);
-        InputModel { lines }
+        Ok(InputModel { lines })
     }
 }
 
-fn default_input() -> &'static str {
-    include_input!(00)
-}
+impl Solution for Day00 {
+    const DAY: u32 = 0;
 
-pub fn part1() -> String {
-    let model = InputModel::from(default_input());
-    model.lines.len().to_string()
-}
+    type Model = InputModel;
+    type Answer1 = usize;
+    type Answer2 = Answer;
 
-pub fn part2() -> String {
-    let model = InputModel::from(default_input());
-    model.lines.len().to_string()
-}
+    fn default_input() -> &'static str {
+        include_input!(00)
+    }
+
+    fn parse(s: &str) -> Result<InputModel, ParseError> {
+        InputModel::try_from(s)
+    }
+
+    fn part1(model: &InputModel) -> usize {
+        model.lines.len()
+    }
 
-fn main() {
-    println!("{}", part1());
-    println!("{}", part2());
+    fn part2(_model: &InputModel) -> Answer {
+        Answer::Unsolved
+    }
 }
 
+day_main!(Day00);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn solve_part1() {
-        assert_eq!(part1(), "zz");
-    }
-
-    #[test]
-    fn solve_part2() {
-        assert_eq!(part2(), "zz");
-    }
+    answer_tests!(Day00);
 }